@@ -1,13 +1,26 @@
 use log::{error, warn};
 use rayon::prelude::*;
-use std::io::Write;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::process::ExitStatus;
 use std::{env, fs};
 
 use crate::config;
+use crate::diff::{self, DiffOptions};
+use crate::dotenv;
+use crate::filter::{Filter, Selection};
+use crate::input;
 use crate::interactive::InteractiveApp;
+use crate::lint::{self, Problem, Severity};
 use crate::models::*;
+use crate::output;
+use crate::path_list::{self, PathStatus};
+use crate::redact::Redactor;
+use crate::snapshot::{self, Snapshot, SnapshotStore};
+use crate::sort;
+use crate::template::Template;
 use crate::utils::*;
 use crate::variables;
 
@@ -17,7 +30,19 @@ pub fn run_command<W: Write>(
     config: Option<Config>,
     mut buffer: W,
 ) -> ExitCode {
-    match command {
+    // Apply default options for running process from config
+    let mut command = command.clone();
+    if let Some(run) = command.run_args_mut()
+        && !run.shell
+        && config.as_ref().and_then(|config| config.no_shell) == Some(true)
+    {
+        run.no_shell = true;
+    }
+    let names = config
+        .as_ref()
+        .and_then(|config| config.name_validation)
+        .unwrap_or_default();
+    match &command {
         Commands::InitConfig => {
             if let Err(error) = config::init_config(config::get_config_file_path(), buffer) {
                 error!("{}", error);
@@ -25,238 +50,934 @@ pub fn run_command<W: Write>(
             }
         }
         Commands::Get(opt) => {
-            if let Err(error) = get(opt, &mut buffer) {
+            let redactor = match Redactor::new(config.as_ref(), opt.reveal) {
+                Ok(redactor) => redactor,
+                Err(error) => {
+                    error!("{}", error);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let errors = get(opt, &redactor, &mut buffer);
+            for error in &errors {
                 error!("{}", error);
-                if let ErrorKind::CannotFindVariable(key, no_similar_names) = error {
-                    if !no_similar_names {
-                        let similar_names = find_similar_string(
-                            key.clone(),
-                            env::vars().map(|(key, _)| key).collect(),
-                            0.6,
-                        );
-                        if !similar_names.is_empty() {
-                            writeln!(&mut buffer, "Did you mean:")
+                // Keep output of raw values clean for scripts
+                if let ErrorKind::CannotFindVariable(key, no_similar_names) = error
+                    && !no_similar_names
+                    && !opt.raw
+                    && !opt.null
+                {
+                    let similar_names = find_similar_string(
+                        key.clone(),
+                        env::vars().map(|(key, _)| key).collect(),
+                        0.6,
+                    );
+                    if !similar_names.is_empty() {
+                        writeln!(&mut buffer, "Did you mean:").expect("Failed to write to buffer");
+                        for name in similar_names {
+                            writeln!(&mut buffer, "  {}", &name)
                                 .expect("Failed to write to buffer");
-                            for name in similar_names {
-                                writeln!(&mut buffer, "  {}", &name)
-                                    .expect("Failed to write to buffer");
-                            }
                         }
                     }
                 }
+            }
+            // All variables are missing
+            if errors.len() == opt.keys.len() {
                 return ExitCode::FAILURE;
             }
+            // Only some of variables are missing
+            if !errors.is_empty() && !opt.allow_missing {
+                return ExitCode::from(2);
+            }
         }
         Commands::Print(opt) => {
-            let opt = if opt.format.is_some() {
-                opt
-            } else if let Some(config) = config {
-                &PrintArgs {
-                    format: config.print_format,
+            let redactor = match Redactor::new(config.as_ref(), opt.reveal) {
+                Ok(redactor) => redactor,
+                Err(error) => {
+                    error!("{}", error);
+                    return ExitCode::FAILURE;
                 }
-            } else {
-                opt
             };
-            print_env(opt, buffer)
-        }
-        Commands::Load(opt) => match load(opt) {
-            Ok(code) => {
-                if let Some(exit_code) = code {
-                    return ExitCode::from(exit_code.code().unwrap_or_default() as u8);
-                }
-            }
-            Err(error) => {
-                error!("{}", error);
-                return ExitCode::FAILURE;
-            }
-        },
-        Commands::Set(opt) => match set(opt) {
-            Ok(code) => {
-                if let Some(exit_code) = code {
-                    return ExitCode::from(exit_code.code().unwrap_or_default() as u8);
-                }
-            }
-            Err(error) => {
-                error!("{}", error);
-                return ExitCode::FAILURE;
-            }
-        },
-        Commands::Add(opt) => match add(opt) {
-            Ok(code) => {
-                if let Some(exit_code) = code {
-                    return ExitCode::from(exit_code.code().unwrap_or_default() as u8);
+            let mut opt = opt.clone();
+            if let Some(config) = config {
+                if opt.format.is_none() && opt.output.is_none() {
+                    opt.format = config.print_format;
                 }
-            }
-            Err(error) => {
-                error!("{}", error);
-                return ExitCode::FAILURE;
-            }
-        },
-        Commands::Delete(opt) => match delete(opt) {
-            Ok(code) => {
-                if let Some(exit_code) = code {
-                    return ExitCode::from(exit_code.code().unwrap_or_default() as u8);
+                if opt.sort.is_none() {
+                    opt.sort = config.print_sort;
                 }
             }
-            Err(error) => {
+            if let Err(error) = print_env(&opt, &redactor, buffer) {
                 error!("{}", error);
                 return ExitCode::FAILURE;
             }
-        },
+        }
+        Commands::Load(opt) => {
+            return process_exit_code(
+                diff_options(config.as_ref(), opt.dry_run, opt.reveal)
+                    .and_then(|options| load(opt, names, &options, buffer)),
+            );
+        }
+        Commands::Set(opt) => {
+            return process_exit_code(
+                diff_options(config.as_ref(), opt.dry_run, opt.reveal)
+                    .and_then(|options| set(opt, names, &options, buffer)),
+            );
+        }
+        Commands::Add(opt) => {
+            return process_exit_code(
+                diff_options(config.as_ref(), opt.dry_run, opt.reveal)
+                    .and_then(|options| add(opt, names, &options, buffer)),
+            );
+        }
+        Commands::Prepend(opt) => {
+            return process_exit_code(
+                diff_options(config.as_ref(), opt.dry_run, opt.reveal)
+                    .and_then(|options| prepend(opt, names, &options, buffer)),
+            );
+        }
+        Commands::Delete(opt) => {
+            return process_exit_code(
+                diff_options(config.as_ref(), opt.dry_run, opt.reveal)
+                    .and_then(|options| delete(opt, names, &options, buffer)),
+            );
+        }
         Commands::Interactive => {
             #[cfg(not(test))]
             let mut terminal = ratatui::init();
             #[cfg(test)]
             let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 100))
                 .expect("Failed to create TestBackend terminal");
-            let result = InteractiveApp::new().run(&mut terminal);
+            let redactor = match Redactor::new(config.as_ref(), false) {
+                Ok(redactor) => redactor,
+                Err(error) => {
+                    error!("{}", error);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let result = InteractiveApp::new(redactor).run(&mut terminal);
             ratatui::restore();
             if let Err(error) = result {
                 error!("{}", error);
                 return ExitCode::FAILURE;
             }
         }
-        Commands::Export(opt) => match export(opt) {
-            Ok(()) => {}
-            Err(error) => {
+        Commands::Export(opt) => {
+            // Secrets are masked only if placeholders are requested
+            let redactor = if opt.placeholders {
+                Redactor::new(config.as_ref(), false)
+            } else {
+                Ok(Redactor::disabled())
+            };
+            if let Err(error) =
+                redactor.and_then(|redactor| export(opt, names, &redactor, &mut buffer))
+            {
                 error!("{error}");
                 return ExitCode::FAILURE;
             }
+        }
+        Commands::Path(opt) => {
+            let (dry_run, reveal) = match &opt.command {
+                PathCommands::Append(args)
+                | PathCommands::Prepend(args)
+                | PathCommands::Remove(args) => (args.dry_run, args.reveal),
+                PathCommands::Dedupe(args) => (args.dry_run, args.reveal),
+                PathCommands::List(_) | PathCommands::Check(_) => (false, false),
+            };
+            return process_exit_code(
+                diff_options(config.as_ref(), dry_run, reveal)
+                    .and_then(|options| path(&opt.command, names, &options, buffer)),
+            );
+        }
+        Commands::Snapshot(opt) => {
+            let reveal = match &opt.command {
+                SnapshotCommands::Show(args) => args.reveal,
+                SnapshotCommands::Diff(args) => args.reveal,
+                _ => false,
+            };
+            let store = SnapshotStore::new(config::get_snapshots_dir());
+            return process_exit_code(
+                DiffOptions::new(config.as_ref(), reveal)
+                    .and_then(|options| snapshot(&opt.command, &store, &options, buffer)),
+            );
+        }
+        Commands::Lint(opt) => match lint_files(opt, names, buffer) {
+            Ok(true) => {}
+            Ok(false) => return ExitCode::FAILURE,
+            Err(error) => {
+                error!("{}", error);
+                return ExitCode::FAILURE;
+            }
         },
     }
     ExitCode::SUCCESS
 }
 
+/// Get options for showing changes, secret patterns from config are read only for dry run,
+/// so invalid patterns don't break commands that don't print changes
+fn diff_options(
+    config: Option<&Config>,
+    dry_run: bool,
+    reveal: bool,
+) -> Result<DiffOptions, ErrorKind> {
+    if dry_run {
+        DiffOptions::new(config, reveal)
+    } else {
+        Ok(DiffOptions::default())
+    }
+}
+
+/// Get exit code for result of command, which optionally runs process
+fn process_exit_code(result: Result<Option<ExitStatus>, ErrorKind>) -> ExitCode {
+    match result {
+        Ok(Some(status)) => exit_code(status),
+        Ok(None) => ExitCode::SUCCESS,
+        Err(error) => {
+            error!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
 /// Print all environment variables
-pub fn print_env<W: Write>(opt: &PrintArgs, buffer: W) {
-    let format = &opt
-        .format
-        .clone()
-        .unwrap_or("{name} = \"{value}\"".to_owned());
+pub fn print_env<W: Write>(
+    opt: &PrintArgs,
+    redactor: &Redactor,
+    mut buffer: W,
+) -> Result<(), ErrorKind> {
+    let filter = Filter::new(&opt.filter)?;
+    let mut variables = filter.apply(variables::get_variables());
+    match opt.sort {
+        Some(key) => sort::sort_variables(&mut variables, key, opt.reverse),
+        None if opt.reverse => variables.reverse(),
+        None => {}
+    }
+    let variables = redactor.apply(variables);
+    // Print variables in structured format if it is specified
+    if let Some(output) = opt.output {
+        return output::write_variables(&variables, output, buffer);
+    }
+    let template = Template::parse(opt.format.as_deref().unwrap_or("{name} = \"{value}\""))?;
+    if opt.group_by_prefix {
+        let mut index = 0;
+        for (group_index, (prefix, group)) in sort::group_by_prefix(variables).iter().enumerate() {
+            if group_index > 0 {
+                writeln!(buffer).expect("Failed to write to buffer");
+            }
+            if let Some(prefix) = prefix {
+                writeln!(buffer, "[{}]", prefix).expect("Failed to write to buffer");
+            }
+            for (name, value) in group {
+                writeln!(buffer, "{}", template.render(name, value, index))
+                    .expect("Failed to write to buffer");
+                index += 1;
+            }
+        }
+        return Ok(());
+    }
     // Print all environment variables
-    variables::print_env(format, buffer);
+    variables::print_env(&variables, &template, buffer);
+    Ok(())
 }
 
-/// Load variables from dotenv-style file
-pub fn load(args: &LoadArgs) -> Result<Option<ExitStatus>, ErrorKind> {
-    // Try to read file
-    match fs::read_to_string(&args.file) {
-        Ok(content) => {
-            // Try to parse file
-            match dotenv_parser::parse_dotenv(&content) {
-                Ok(variables) => {
-                    variables.into_par_iter().try_for_each(
-                        |(key, value)| -> Result<(), ErrorKind> {
-                            variables::set_variable(&key, &value, args.global)
-                        },
-                    )?;
-                    if !args.process.is_empty() {
-                        let process = args.process.join(" ");
-                        return run(process).map(Some);
-                    }
-                }
-                Err(err) => {
-                    return Err(ErrorKind::ParsingError(err.to_string()));
-                }
+/// Load variables from dotenv-style files, standard input or inline content.
+///
+/// Variables are applied one by one in deterministic order and passed to
+/// process explicitly
+pub fn load<W: Write>(
+    args: &LoadArgs,
+    names: NameValidation,
+    options: &DiffOptions,
+    mut buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    let loaded = read_env_inputs(&env_inputs(args), names)?;
+    if args.explain {
+        for (key, sources) in &loaded.sources {
+            let (source, overridden) = sources.split_last().expect("Variable has no source");
+            if overridden.is_empty() {
+                writeln!(buffer, "{}: {}", key, source)
+            } else {
+                writeln!(
+                    buffer,
+                    "{}: {} (overrides {})",
+                    key,
+                    source,
+                    overridden.join(", ")
+                )
             }
+            .expect("Failed to write to buffer");
         }
-        Err(err) => {
-            return Err(ErrorKind::FileError(err.to_string()));
+    }
+    if args.dry_run {
+        let updates = loaded
+            .values
+            .into_iter()
+            .map(|(key, value)| (key, Some(value)))
+            .collect();
+        preview(updates, options, buffer);
+        return Ok(None);
+    }
+    if args.explain {
+        return Ok(None);
+    }
+    if args.global {
+        for (key, value) in &loaded.values {
+            variables::set_variable(key, value, true)?;
         }
     }
+    if !args.process.is_empty() {
+        let env = ChildEnv::with_vars(loaded.values.into_iter().collect());
+        return run(&args.process, &args.run, &env).map(Some);
+    }
     Ok(None)
 }
 
-/// Get value of variable
-pub fn get<W: Write>(args: &GetArgs, mut buffer: W) -> Result<(), ErrorKind> {
-    // Check if variable with specified name exists
-    match env::var(&args.key) {
-        Ok(value) => writeln!(buffer, "{:?}", &value).expect("Failed to write to buffer"),
-        // If variable not found
-        _ => {
-            return Err(ErrorKind::CannotFindVariable(
-                args.key.clone(),
-                args.no_similar_names,
-            ));
+/// Source of variables to load
+#[derive(Debug, PartialEq, Eq)]
+enum EnvSource {
+    /// File at given path, optional files are skipped if they don't exist
+    File { path: String, optional: bool },
+    /// Standard input, used for `-` path
+    Stdin,
+    /// Content given in command line
+    Inline(String),
+}
+
+impl EnvSource {
+    /// Read content of source
+    fn read(&self) -> Result<String, ErrorKind> {
+        match self {
+            EnvSource::File { path, .. } => {
+                fs::read_to_string(path).map_err(|err| ErrorKind::FileError(err.to_string()))
+            }
+            EnvSource::Stdin => {
+                let mut content = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|err| ErrorKind::FileError(err.to_string()))?;
+                Ok(content)
+            }
+            EnvSource::Inline(content) => Ok(input::unescape_newlines(content)),
         }
     }
-    Ok(())
 }
 
-/// Set value to environment variable
-pub fn set(args: &SetArgs) -> Result<Option<ExitStatus>, ErrorKind> {
-    validate_var_name(&args.key).map_err(ErrorKind::NameValidationError)?;
+impl std::fmt::Display for EnvSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvSource::File { path, .. } => write!(f, "{}", path),
+            EnvSource::Stdin => write!(f, "<stdin>"),
+            EnvSource::Inline(_) => write!(f, "<env-string>"),
+        }
+    }
+}
+
+/// Input to load variables from
+#[derive(Debug, PartialEq, Eq)]
+struct EnvInput {
+    source: EnvSource,
+    format: InputFormat,
+}
+
+/// Get list of inputs to load in order of increasing precedence.
+///
+/// Inline content is loaded after files, so it overrides them
+fn env_inputs(args: &LoadArgs) -> Vec<EnvInput> {
+    let format = |path: &str| args.format.unwrap_or_else(|| input::detect_format(path));
+    let mut inputs: Vec<EnvInput> = match &args.cascade {
+        Some(name) => [
+            ".env".to_owned(),
+            ".env.local".to_owned(),
+            format!(".env.{}", name),
+            format!(".env.{}.local", name),
+        ]
+        .into_iter()
+        .map(|path| EnvInput {
+            format: args.format.unwrap_or(InputFormat::Dotenv),
+            source: EnvSource::File {
+                path,
+                optional: true,
+            },
+        })
+        .collect(),
+        None => args
+            .file
+            .iter()
+            .map(|path| EnvInput {
+                format: format(path),
+                source: if path == "-" {
+                    EnvSource::Stdin
+                } else {
+                    EnvSource::File {
+                        path: path.clone(),
+                        optional: false,
+                    }
+                },
+            })
+            .collect(),
+    };
+    // .env is used only if nothing else is given
+    if inputs.is_empty() && args.env_string.is_empty() {
+        inputs.push(EnvInput {
+            format: format(".env"),
+            source: EnvSource::File {
+                path: ".env".to_owned(),
+                optional: false,
+            },
+        });
+    }
+    inputs.extend(args.env_string.iter().map(|content| EnvInput {
+        format: args.format.unwrap_or(InputFormat::Dotenv),
+        source: EnvSource::Inline(content.clone()),
+    }));
+    inputs
+}
+
+/// Variables loaded from several inputs
+#[derive(Debug, Default)]
+struct LoadedVariables {
+    /// Final values of variables
+    values: BTreeMap<String, String>,
+    /// Inputs, which defined each variable, last one wins
+    sources: BTreeMap<String, Vec<String>>,
+}
+
+/// Read variables from inputs, later inputs override earlier ones.
+///
+/// References in each input can use variables from previous ones
+fn read_env_inputs(
+    inputs: &[EnvInput],
+    names: NameValidation,
+) -> Result<LoadedVariables, ErrorKind> {
+    let mut loaded = LoadedVariables::default();
+    let mut found = false;
+    for input in inputs {
+        if let EnvSource::File {
+            path,
+            optional: true,
+        } = &input.source
+            && !Path::new(path).is_file()
+        {
+            continue;
+        }
+        found = true;
+        for (key, value) in read_env_input(input, names, &loaded.values)? {
+            loaded
+                .sources
+                .entry(key.clone())
+                .or_default()
+                .push(input.source.to_string());
+            loaded.values.insert(key, value);
+        }
+    }
+    if !found {
+        let paths: Vec<String> = inputs
+            .iter()
+            .map(|input| input.source.to_string())
+            .collect();
+        return Err(ErrorKind::FileError(format!(
+            "None of files exist: {}",
+            paths.join(", ")
+        )));
+    }
+    Ok(loaded)
+}
+
+/// Read and validate variables from input.
+///
+/// References are looked up in `loaded` variables before existing environment
+fn read_env_input(
+    input: &EnvInput,
+    names: NameValidation,
+    loaded: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, ErrorKind> {
+    let content = input.source.read()?;
+    let entries = input::parse(&content, input.format)?;
+    entries.par_iter().try_for_each(|entry| {
+        validate_var_name(&entry.key, names).map_err(ErrorKind::NameValidationError)
+    })?;
+    dotenv::resolve(&entries, |name| {
+        loaded.get(name).cloned().or_else(|| env::var(name).ok())
+    })
+}
+
+/// Get values of variables.
+///
+/// Values are printed in order of given keys, missing variables are replaced with
+/// default value if it's set and skipped otherwise. Returns errors for missing variables
+pub fn get<W: Write>(args: &GetArgs, redactor: &Redactor, mut buffer: W) -> Vec<ErrorKind> {
+    let mut errors = vec![];
+    for key in &args.keys {
+        let value = match (env::var(key), &args.default) {
+            (Ok(value), _) => redactor.mask(key, &value).into_owned(),
+            (Err(_), Some(default)) => default.clone(),
+            // If variable not found
+            (Err(_), None) => {
+                errors.push(ErrorKind::CannotFindVariable(
+                    key.clone(),
+                    args.no_similar_names,
+                ));
+                // Raw values are read by position, so empty one is printed to keep it
+                if !args.raw && !args.null {
+                    continue;
+                }
+                String::new()
+            }
+        };
+        if args.null {
+            write!(buffer, "{}\0", value)
+        } else if args.raw {
+            writeln!(buffer, "{}", value)
+        } else {
+            writeln!(buffer, "{:?}", value)
+        }
+        .expect("Failed to write to buffer");
+    }
+    errors
+}
+
+/// Set values to environment variables.
+///
+/// All names are validated before setting any of variables
+pub fn set<W: Write>(
+    args: &SetArgs,
+    names: NameValidation,
+    options: &DiffOptions,
+    buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    let variables = parse_assignments(&args.variables)?;
+    for (key, _) in &variables {
+        validate_var_name(key, names).map_err(ErrorKind::NameValidationError)?;
+    }
+    if args.dry_run {
+        let updates = variables
+            .into_iter()
+            .map(|(key, value)| (key, Some(value)))
+            .collect();
+        preview(updates, options, buffer);
+        return Ok(None);
+    }
 
-    variables::set_variable(&args.key, &args.value, args.global)?;
+    if args.global {
+        for (key, value) in &variables {
+            variables::set_variable(key, value, true)?;
+        }
+    }
     if !args.process.is_empty() {
-        let process = args.process.join(" ");
-        return run(process).map(Some);
+        return run(&args.process, &args.run, &ChildEnv::with_vars(variables)).map(Some);
     }
     Ok(None)
 }
 
-/// Add value to environment variable
-pub fn add(args: &AddArgs) -> Result<Option<ExitStatus>, ErrorKind> {
-    validate_var_name(&args.key).map_err(ErrorKind::NameValidationError)?;
+/// Parse args of set command, which are either `NAME=VALUE` pairs or name followed by value
+fn parse_assignments(args: &[String]) -> Result<Vec<(String, String)>, ErrorKind> {
+    if let [key, value] = args
+        && !key.contains('=')
+    {
+        return Ok(vec![(key.clone(), value.clone())]);
+    }
+    args.iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((key, value)) => Ok((key.to_owned(), value.to_owned())),
+            None => Err(ErrorKind::ParsingError(format!(
+                "expected NAME=VALUE, got '{}'",
+                arg
+            ))),
+        })
+        .collect()
+}
+
+/// Add value to the end of environment variable
+pub fn add<W: Write>(
+    args: &AddArgs,
+    names: NameValidation,
+    options: &DiffOptions,
+    buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    update_variable(args, names, options, buffer, |current, value| {
+        format!("{}{}", current, value)
+    })
+}
+
+/// Add value to the beginning of environment variable
+pub fn prepend<W: Write>(
+    args: &AddArgs,
+    names: NameValidation,
+    options: &DiffOptions,
+    buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    update_variable(args, names, options, buffer, |current, value| {
+        format!("{}{}", value, current)
+    })
+}
+
+/// Combine current value of variable with given one and run process if it is specified
+fn update_variable<W: Write>(
+    args: &AddArgs,
+    names: NameValidation,
+    options: &DiffOptions,
+    buffer: W,
+    combine: impl Fn(&str, &str) -> String,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    validate_var_name(&args.key, names).map_err(ErrorKind::NameValidationError)?;
 
     let current_value = if let Ok(value) = env::var(&args.key) {
         value
     } else {
         "".to_string()
     };
+    // Separator is needed only if there is something to separate
+    let value = match &args.separator {
+        Some(separator) if !current_value.is_empty() => {
+            combine(&current_value, &combine(separator, &args.value))
+        }
+        _ => combine(&current_value, &args.value),
+    };
 
-    variables::set_variable(
-        &args.key,
-        &format!("{}{}", current_value, args.value),
-        args.global,
-    )?;
+    if args.dry_run {
+        preview(vec![(args.key.clone(), Some(value))], options, buffer);
+        return Ok(None);
+    }
+    if args.global {
+        variables::set_variable(&args.key, &value, true)?;
+    }
     if !args.process.is_empty() {
-        let process = args.process.join(" ");
-        return run(process).map(Some);
+        let env = ChildEnv::with_vars(vec![(args.key.clone(), value)]);
+        return run(&args.process, &args.run, &env).map(Some);
     }
     Ok(None)
 }
 
-/// Delete environment variable
-pub fn delete(args: &DeleteArgs) -> Result<Option<ExitStatus>, ErrorKind> {
-    validate_var_name(&args.key).map_err(ErrorKind::NameValidationError)?;
+/// Delete environment variables.
+///
+/// All names are validated before deleting any of variables
+pub fn delete<W: Write>(
+    args: &DeleteArgs,
+    names: NameValidation,
+    options: &DiffOptions,
+    buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    for key in &args.keys {
+        validate_var_name(key, names).map_err(ErrorKind::NameValidationError)?;
+    }
+    if args.dry_run {
+        let updates = args.keys.iter().map(|key| (key.clone(), None)).collect();
+        preview(updates, options, buffer);
+        return Ok(None);
+    }
 
-    // Check if variable exists
-    match env::var(&args.key) {
-        Ok(_) => {
-            variables::delete_variable(args.key.clone(), args.global)?;
-        }
-        _ => {
-            warn!("{}", "variable doesn't exists");
+    for key in &args.keys {
+        // Check if variable exists
+        match env::var(key) {
+            Ok(_) if args.global => {
+                variables::delete_variable(key.clone(), true)?;
+            }
+            Ok(_) => {}
+            _ => {
+                warn!("variable {} doesn't exists", key);
+            }
         }
     }
     if !args.process.is_empty() {
-        let process = args.process.join(" ");
-        return run(process).map(Some);
+        let env = ChildEnv {
+            removed: args.keys.clone(),
+            ..Default::default()
+        };
+        return run(&args.process, &args.run, &env).map(Some);
+    }
+    Ok(None)
+}
+
+/// Print changes, which updates of variables would make to current environment.
+///
+/// `None` value means deleting variable
+fn preview<W: Write>(updates: Vec<(String, Option<String>)>, options: &DiffOptions, buffer: W) {
+    let changes = diff::changes(&updates, |name| env::var(name).ok());
+    options.write(changes, buffer);
+}
+
+/// Run operation with snapshots of environment, saved in given store
+pub fn snapshot<W: Write>(
+    command: &SnapshotCommands,
+    store: &SnapshotStore,
+    options: &DiffOptions,
+    mut buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    match command {
+        SnapshotCommands::Save(args) => {
+            let saved = Snapshot::new(variables::get_variables());
+            store.save(&args.name, &saved, args.force)?;
+            writeln!(
+                buffer,
+                "Saved snapshot {} with {} variables",
+                args.name,
+                saved.variables.len()
+            )
+            .expect("Failed to write to buffer");
+        }
+        SnapshotCommands::List => {
+            let names = store.names()?;
+            if names.is_empty() {
+                writeln!(buffer, "No snapshots saved").expect("Failed to write to buffer");
+            }
+            for name in names {
+                let saved = store.load(&name)?;
+                writeln!(
+                    buffer,
+                    "{}: {} variables, saved {}",
+                    name,
+                    saved.variables.len(),
+                    snapshot::format_timestamp(saved.created)
+                )
+                .expect("Failed to write to buffer");
+            }
+        }
+        SnapshotCommands::Show(args) => {
+            let variables = options
+                .redactor
+                .apply(store.load(&args.name)?.variables.into_iter().collect());
+            match args.output {
+                Some(output) => output::write_variables(&variables, output, buffer)?,
+                None => variables::print_env(
+                    &variables,
+                    &Template::parse("{name} = \"{value}\"")?,
+                    buffer,
+                ),
+            }
+        }
+        SnapshotCommands::Diff(args) => {
+            let from = snapshot_variables(store, &args.from)?;
+            let to = snapshot_variables(store, &args.to)?;
+            let updates: Vec<(String, Option<String>)> = from
+                .keys()
+                .filter(|name| !to.contains_key(*name))
+                .map(|name| (name.clone(), None))
+                .chain(
+                    to.iter()
+                        .map(|(name, value)| (name.clone(), Some(value.clone()))),
+                )
+                .collect();
+            let mut changes = diff::changes(&updates, |name| from.get(name).cloned());
+            changes.sort_by(|a, b| change_name(a).cmp(change_name(b)));
+            options.write(changes, buffer);
+        }
+        SnapshotCommands::Restore(args) => {
+            let saved = store.load(&args.name)?;
+            // Variables, which appeared after saving snapshot, are removed
+            let env = ChildEnv {
+                removed: variables::get_variables()
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .filter(|name| !saved.variables.contains_key(name))
+                    .collect(),
+                vars: saved.variables.into_iter().collect(),
+            };
+            return run(&args.process, &args.run, &env).map(Some);
+        }
     }
     Ok(None)
 }
 
-pub fn export(args: &ExportArgs) -> Result<(), ErrorKind> {
-    let mut file = fs::File::create(format!("{}.env", args.file_name.trim()))
-        .map_err(|e| ErrorKind::FileError(e.to_string()))?;
+/// Get variables from snapshot with given name, or from current environment
+fn snapshot_variables(
+    store: &SnapshotStore,
+    name: &str,
+) -> Result<BTreeMap<String, String>, ErrorKind> {
+    if name == snapshot::CURRENT {
+        Ok(variables::get_variables().into_iter().collect())
+    } else {
+        Ok(store.load(name)?.variables)
+    }
+}
+
+/// Get name of variable, changed by change
+fn change_name(change: &diff::Change) -> &str {
+    match change {
+        diff::Change::Added { name, .. }
+        | diff::Change::Modified { name, .. }
+        | diff::Change::Removed { name, .. } => name,
+    }
+}
+
+/// Run operation with PATH-style variable
+pub fn path<W: Write>(
+    command: &PathCommands,
+    names: NameValidation,
+    options: &DiffOptions,
+    mut buffer: W,
+) -> Result<Option<ExitStatus>, ErrorKind> {
+    match command {
+        PathCommands::List(args) => {
+            let value = env::var(&args.key)
+                .map_err(|_| ErrorKind::CannotFindVariable(args.key.clone(), false))?;
+            for entry in path_list::split(&value) {
+                writeln!(buffer, "{}", entry.display()).expect("Failed to write to buffer");
+            }
+            Ok(None)
+        }
+        PathCommands::Check(args) => {
+            let value = env::var(&args.key)
+                .map_err(|_| ErrorKind::CannotFindVariable(args.key.clone(), false))?;
+            let entries = path_list::split(&value);
+            let mut problems = 0;
+            for (entry, status) in path_list::check(&entries) {
+                if status != PathStatus::Ok {
+                    problems += 1;
+                }
+                writeln!(buffer, "{}: {}", entry.display(), status)
+                    .expect("Failed to write to buffer");
+            }
+            if problems > 0 {
+                return Err(ErrorKind::PathListError(format!(
+                    "{} of {} entries of {} have problems",
+                    problems,
+                    entries.len(),
+                    args.key
+                )));
+            }
+            Ok(None)
+        }
+        PathCommands::Append(args) | PathCommands::Prepend(args) | PathCommands::Remove(args) => {
+            validate_var_name(&args.key, names).map_err(ErrorKind::NameValidationError)?;
+            let paths: Vec<PathBuf> = args.paths.iter().map(PathBuf::from).collect();
+            let entries = match (command, env::var(&args.key)) {
+                (PathCommands::Append(_), value) => {
+                    path_list::append(path_list::split(&value.unwrap_or_default()), &paths)
+                }
+                (PathCommands::Prepend(_), value) => {
+                    path_list::prepend(path_list::split(&value.unwrap_or_default()), &paths)
+                }
+                (_, Ok(value)) => {
+                    let entries = path_list::split(&value);
+                    for path in paths.iter().filter(|path| !entries.contains(path)) {
+                        warn!("path {} not found in {}", path.display(), args.key);
+                    }
+                    path_list::remove(entries, &paths)
+                }
+                (_, Err(_)) => return Err(ErrorKind::CannotFindVariable(args.key.clone(), false)),
+            };
+            let value = path_list::join(&entries)?;
+            if args.dry_run {
+                preview(vec![(args.key.clone(), Some(value))], options, buffer);
+                return Ok(None);
+            }
+            if args.global {
+                variables::set_variable(&args.key, &value, true)?;
+            }
+            if !args.process.is_empty() {
+                let env = ChildEnv::with_vars(vec![(args.key.clone(), value)]);
+                return run(&args.process, &args.run, &env).map(Some);
+            }
+            Ok(None)
+        }
+        PathCommands::Dedupe(args) => {
+            validate_var_name(&args.key, names).map_err(ErrorKind::NameValidationError)?;
+            let value = env::var(&args.key)
+                .map_err(|_| ErrorKind::CannotFindVariable(args.key.clone(), false))?;
+            let entries = path_list::dedupe(path_list::split(&value));
+            let value = path_list::join(&entries)?;
+            if args.dry_run {
+                preview(vec![(args.key.clone(), Some(value))], options, buffer);
+                return Ok(None);
+            }
+            if args.global {
+                variables::set_variable(&args.key, &value, true)?;
+            }
+            if !args.process.is_empty() {
+                let env = ChildEnv::with_vars(vec![(args.key.clone(), value)]);
+                return run(&args.process, &args.run, &env).map(Some);
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Problem found in file, used in JSON report
+#[derive(serde::Serialize)]
+struct FileProblem<'a> {
+    file: &'a str,
+    #[serde(flatten)]
+    problem: &'a Problem,
+}
 
-    let mut added_vars: Vec<String> = Vec::new();
+/// Check dotenv files and print report.
+///
+/// Returns whether files passed check, i.e. have no errors, or no warnings in strict mode
+pub fn lint_files<W: Write>(
+    args: &LintArgs,
+    names: NameValidation,
+    mut buffer: W,
+) -> Result<bool, ErrorKind> {
+    let mut reports = vec![];
+    for file in &args.files {
+        let content =
+            fs::read_to_string(file).map_err(|err| ErrorKind::FileError(err.to_string()))?;
+        reports.push((file.as_str(), lint::lint(&content, names)));
+    }
+    let problems: Vec<FileProblem> = reports
+        .iter()
+        .flat_map(|(file, problems)| problems.iter().map(|problem| FileProblem { file, problem }))
+        .collect();
+    let errors = problems
+        .iter()
+        .filter(|item| item.problem.severity == Severity::Error)
+        .count();
+    let warnings = problems.len() - errors;
+
+    match args.output {
+        ReportFormat::Human => {
+            for FileProblem { file, problem } in &problems {
+                let severity = match problem.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                writeln!(
+                    buffer,
+                    "{}:{}:{}: {}: {}",
+                    file, problem.line, problem.column, severity, problem.message
+                )
+                .expect("Failed to write to buffer");
+            }
+            if problems.is_empty() {
+                writeln!(buffer, "No problems found")
+            } else {
+                writeln!(buffer, "Found {} errors and {} warnings", errors, warnings)
+            }
+            .expect("Failed to write to buffer");
+        }
+        ReportFormat::Json => {
+            let json = serde_json::to_string_pretty(&problems)
+                .map_err(|err| ErrorKind::SerializationError(err.to_string()))?;
+            writeln!(buffer, "{}", json).expect("Failed to write to buffer");
+        }
+    }
+    Ok(errors == 0 && (!args.strict || warnings == 0))
+}
 
+/// Export variables to file or buffer, masking secrets with given redactor
+pub fn export<W: Write>(
+    args: &ExportArgs,
+    names: NameValidation,
+    redactor: &Redactor,
+    mut buffer: W,
+) -> Result<(), ErrorKind> {
+    let selection = Selection::new(args)?;
     for key in &args.keys {
-        validate_var_name(key).map_err(ErrorKind::NameValidationError)?;
+        validate_var_name(key, names).map_err(ErrorKind::NameValidationError)?;
+    }
 
+    let mut variables: Vec<(String, String)> = Vec::new();
+    for key in &args.keys {
+        if selection.is_excluded(key) {
+            continue;
+        }
         match env::var(key) {
             Ok(value) => {
-                if added_vars.contains(key) {
+                if variables.iter().any(|(name, _)| name == key) {
                     warn!("Duplicate var {key} found, skipping");
                     continue;
                 };
 
-                writeln!(file, "{}={}", key, &value)
-                    .map_err(|e| ErrorKind::FileError(e.to_string()))?;
-
-                added_vars.push(key.to_string());
+                variables.push((key.clone(), redactor.mask(key, &value).into_owned()));
             }
             Err(_) => {
                 warn!(
@@ -267,6 +988,59 @@ pub fn export(args: &ExportArgs) -> Result<(), ErrorKind> {
         }
     }
 
+    // Add variables matched by patterns, reporting what each pattern matched
+    let current = variables::get_variables();
+    let current_names: Vec<&str> = current.iter().map(|(name, _)| name.as_str()).collect();
+    for (pattern, matched) in selection.matches(&current_names) {
+        if matched.is_empty() {
+            warn!("Pattern {pattern} didn't match any variable");
+            continue;
+        }
+        // Keep exported content clean, so report always goes to stderr
+        eprintln!("{}: {}", pattern, matched.join(", "));
+        for name in matched {
+            if !variables.iter().any(|(added, _)| *added == name)
+                && let Some((_, value)) = current.iter().find(|(current, _)| *current == name)
+            {
+                let value = redactor.mask(&name, value).into_owned();
+                variables.push((name, value));
+            }
+        }
+    }
+
+    let path = args.file_name.trim();
+    let format = args
+        .output
+        .unwrap_or_else(|| output::detect_export_format(path));
+    if path == "-" {
+        if args.merge {
+            return Err(ErrorKind::FileError(
+                "Can't merge variables into stdout".to_owned(),
+            ));
+        }
+        let content = output::serialize_export(&variables, format)?;
+        buffer
+            .write_all(content.as_bytes())
+            .expect("Failed to write to buffer");
+        return Ok(());
+    }
+
+    let content = if args.merge {
+        if format != ExportFormat::Dotenv {
+            return Err(ErrorKind::FileError(
+                "Only dotenv files can be merged".to_owned(),
+            ));
+        }
+        let existing = match fs::read_to_string(path) {
+            Ok(existing) => existing,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(ErrorKind::FileError(format!("{}: {}", path, err))),
+        };
+        output::merge_dotenv(&existing, &variables)?
+    } else {
+        output::serialize_export(&variables, format)?
+    };
+    write_file_atomically(Path::new(path), &content, args.no_clobber)?;
     Ok(())
 }
 
@@ -288,8 +1062,9 @@ mod tests {
         let mut buffer = vec![];
         run_command(
             &Commands::Get(GetArgs {
-                key: "TEST_RUN_VAR".to_string(),
+                keys: vec!["TEST_RUN_VAR".to_string()],
                 no_similar_names: false,
+                ..Default::default()
             }),
             None,
             &mut buffer,
@@ -309,8 +1084,9 @@ mod tests {
         assert_eq!(
             run_command(
                 &Commands::Get(GetArgs {
-                    key: "TEST_RUN_VAR_awzsenfkaqyG".to_string(),
+                    keys: vec!["TEST_RUN_VAR_awzsenfkaqyG".to_string()],
                     no_similar_names: false,
+                    ..Default::default()
                 }),
                 None,
                 &mut buffer
@@ -325,17 +1101,18 @@ mod tests {
         let mut buffer = vec![];
         run_command(
             &Commands::Set(SetArgs {
-                key: "TEST_SET_RUN".to_string(),
-                value: "test_value".to_string(),
+                variables: vec!["TEST_SET_RUN".to_string(), "test_value".to_string()],
                 global: false,
+                dry_run: false,
+                reveal: false,
                 process: vec![],
+                run: RunArgs::default(),
             }),
             None,
             &mut buffer,
         );
 
-        assert_eq!(env::var("TEST_SET_RUN").unwrap(), "test_value");
-        unsafe { env::remove_var("TEST_SET_RUN") };
+        assert!(env::var("TEST_SET_RUN").is_err());
     }
 
     #[test]
@@ -348,13 +1125,17 @@ mod tests {
             &Commands::Add(AddArgs {
                 key: "TEST_ADD_RUN".to_string(),
                 value: "value".to_string(),
+                separator: None,
                 global: false,
+                dry_run: false,
+                reveal: false,
                 process: vec![],
+                run: RunArgs::default(),
             }),
             None,
             &mut buffer,
         );
-        assert_eq!(env::var("TEST_ADD_RUN").unwrap(), "initial_value");
+        assert_eq!(env::var("TEST_ADD_RUN").unwrap(), "initial_");
         unsafe { env::remove_var("TEST_ADD_RUN") };
     }
 
@@ -363,11 +1144,7 @@ mod tests {
         init();
         unsafe { env::set_var("TEST_PRINT_RUN", "test_value") };
         let mut buffer = vec![];
-        run_command(
-            &Commands::Print(PrintArgs { format: None }),
-            None,
-            &mut buffer,
-        );
+        run_command(&Commands::Print(PrintArgs::default()), None, &mut buffer);
         assert!(
             String::from_utf8(buffer)
                 .unwrap()
@@ -383,9 +1160,10 @@ mod tests {
         unsafe { env::set_var("TEST_PRINT_RUN_CONFIG", "test_value") };
         let mut buffer = vec![];
         run_command(
-            &Commands::Print(PrintArgs { format: None }),
+            &Commands::Print(PrintArgs::default()),
             Some(Config {
                 print_format: Some("{name} = {value}".to_owned()),
+                ..Default::default()
             }),
             &mut buffer,
         );
@@ -406,6 +1184,7 @@ mod tests {
         run_command(
             &Commands::Print(PrintArgs {
                 format: Some("{name} = {value}".to_owned()),
+                ..Default::default()
             }),
             None,
             &mut buffer,
@@ -419,53 +1198,347 @@ mod tests {
     }
 
     #[test]
-    fn test_run_command_delete() {
+    fn test_run_command_print_with_output() {
         init();
-        unsafe { env::set_var("TEST_DELETE_RUN", "test_value") };
+        unsafe { env::set_var("TEST_PRINT_RUN_OUTPUT", "say \"hi\"") };
         let mut buffer = vec![];
         run_command(
-            &Commands::Delete(DeleteArgs {
-                key: "TEST_DELETE_RUN".to_string(),
-                global: false,
-                process: vec![],
+            &Commands::Print(PrintArgs {
+                output: Some(OutputFormat::Ndjson),
+                ..Default::default()
+            }),
+            // Format from config shouldn't be used with structured output
+            Some(Config {
+                print_format: Some("{name} = {value}".to_owned()),
+                ..Default::default()
             }),
-            None,
             &mut buffer,
         );
-
-        assert!(env::var("TEST_DELETE_RUN").is_err());
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains(r#"{"name":"TEST_PRINT_RUN_OUTPUT","value":"say \"hi\""}"#));
+        assert!(!output.contains("TEST_PRINT_RUN_OUTPUT = "));
+        unsafe { env::remove_var("TEST_PRINT_RUN_OUTPUT") };
     }
 
     #[test]
-    fn test_run_command_load() {
+    fn test_run_command_print_with_filter_and_config() {
         init();
-        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(temp_file, "TEST_LOAD_RUN=test_value").unwrap();
+        unsafe {
+            env::set_var("TEST_PRINT_FILTER_ONE", "one");
+            env::set_var("TEST_PRINT_FILTER_TWO", "two");
+            env::set_var("TEST_PRINT_UNFILTERED", "three");
+        }
         let mut buffer = vec![];
         run_command(
-            &Commands::Load(LoadArgs {
-                file: temp_file.path().to_string_lossy().to_string(),
-                global: false,
-                process: vec![],
+            &Commands::Print(PrintArgs {
+                filter: FilterArgs {
+                    globs: vec!["TEST_PRINT_FILTER_*".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            Some(Config {
+                print_format: Some("{name}={value}".to_owned()),
+                ..Default::default()
             }),
-            None,
             &mut buffer,
         );
-
-        assert_eq!(env::var("TEST_LOAD_RUN").unwrap(), "test_value");
-        unsafe { env::remove_var("TEST_LOAD_RUN") };
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.contains("TEST_PRINT_FILTER_ONE=one"));
+        assert!(output.contains("TEST_PRINT_FILTER_TWO=two"));
+        assert!(!output.contains("TEST_PRINT_UNFILTERED"));
+        assert!(!output.contains("PATH="));
+        unsafe {
+            env::remove_var("TEST_PRINT_FILTER_ONE");
+            env::remove_var("TEST_PRINT_FILTER_TWO");
+            env::remove_var("TEST_PRINT_UNFILTERED");
+        }
     }
 
     #[test]
-    fn test_print_env_command() {
+    fn test_run_command_print_with_invalid_filter() {
         init();
-        // Set test variable
-        unsafe { env::set_var("TEST_PRINT_VAR", "test_value") };
-
         let mut buffer = vec![];
-        print_env(&PrintArgs { format: None }, &mut buffer);
-        assert!(
-            String::from_utf8(buffer)
+        assert_eq!(
+            run_command(
+                &Commands::Print(PrintArgs {
+                    filter: FilterArgs {
+                        regexes: vec!["(".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                None,
+                &mut buffer,
+            ),
+            ExitCode::FAILURE
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_run_command_print_sorted_by_config() {
+        init();
+        unsafe {
+            env::set_var("TEST_PRINT_SORT_B", "1");
+            env::set_var("TEST_PRINT_SORT_A", "2");
+            env::set_var("TEST_PRINT_SORT_C", "0");
+        }
+        let filter = FilterArgs {
+            prefixes: vec!["TEST_PRINT_SORT_".to_string()],
+            ..Default::default()
+        };
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Print(PrintArgs {
+                format: Some("{name}".to_owned()),
+                filter: filter.clone(),
+                ..Default::default()
+            }),
+            Some(Config {
+                print_sort: Some(SortKey::Name),
+                ..Default::default()
+            }),
+            &mut buffer,
+        );
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "TEST_PRINT_SORT_A\nTEST_PRINT_SORT_B\nTEST_PRINT_SORT_C\n"
+        );
+
+        // Sorting from args overrides config
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Print(PrintArgs {
+                format: Some("{name}".to_owned()),
+                filter,
+                sort: Some(SortKey::Value),
+                reverse: true,
+                ..Default::default()
+            }),
+            Some(Config {
+                print_sort: Some(SortKey::Name),
+                ..Default::default()
+            }),
+            &mut buffer,
+        );
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "TEST_PRINT_SORT_A\nTEST_PRINT_SORT_B\nTEST_PRINT_SORT_C\n"
+        );
+        unsafe {
+            env::remove_var("TEST_PRINT_SORT_A");
+            env::remove_var("TEST_PRINT_SORT_B");
+            env::remove_var("TEST_PRINT_SORT_C");
+        }
+    }
+
+    #[test]
+    fn test_print_env_group_by_prefix() {
+        init();
+        unsafe {
+            env::set_var("TEST_GROUP_ONE_A", "1");
+            env::set_var("TEST_GROUP_TWO_A", "2");
+            env::set_var("TEST_GROUP_ONE_B", "3");
+        }
+        let mut buffer = vec![];
+        print_env(
+            &PrintArgs {
+                format: Some("{name}".to_owned()),
+                filter: FilterArgs {
+                    prefixes: vec!["TEST_GROUP_".to_string()],
+                    ..Default::default()
+                },
+                sort: Some(SortKey::Name),
+                group_by_prefix: true,
+                ..Default::default()
+            },
+            &Redactor::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "[TEST_]\nTEST_GROUP_ONE_A\nTEST_GROUP_ONE_B\nTEST_GROUP_TWO_A\n"
+        );
+        unsafe {
+            env::remove_var("TEST_GROUP_ONE_A");
+            env::remove_var("TEST_GROUP_TWO_A");
+            env::remove_var("TEST_GROUP_ONE_B");
+        }
+    }
+
+    #[test]
+    fn test_print_env_masks_secrets() {
+        init();
+        unsafe { env::set_var("TEST_PRINT_MASK_TOKEN", "very secret") };
+        let args = PrintArgs {
+            format: Some("{name}={value}".to_owned()),
+            filter: FilterArgs {
+                prefixes: vec!["TEST_PRINT_MASK_".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut buffer = vec![];
+        print_env(&args, &Redactor::default(), &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "TEST_PRINT_MASK_TOKEN=********\n"
+        );
+
+        let mut buffer = vec![];
+        print_env(&args, &Redactor::disabled(), &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "TEST_PRINT_MASK_TOKEN=very secret\n"
+        );
+        unsafe { env::remove_var("TEST_PRINT_MASK_TOKEN") };
+    }
+
+    #[test]
+    fn test_run_command_print_reveal() {
+        init();
+        unsafe { env::set_var("TEST_PRINT_REVEAL_PASSWORD", "hunter2") };
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Print(PrintArgs {
+                format: Some("{name}={value}".to_owned()),
+                reveal: true,
+                ..Default::default()
+            }),
+            None,
+            &mut buffer,
+        );
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains("TEST_PRINT_REVEAL_PASSWORD=hunter2")
+        );
+        unsafe { env::remove_var("TEST_PRINT_REVEAL_PASSWORD") };
+    }
+
+    #[test]
+    fn test_run_command_print_with_invalid_format() {
+        init();
+        let mut buffer = vec![];
+        assert_eq!(
+            run_command(
+                &Commands::Print(PrintArgs {
+                    format: Some("{name} = {unknown}".to_owned()),
+                    ..Default::default()
+                }),
+                None,
+                &mut buffer,
+            ),
+            ExitCode::FAILURE
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_print_env_with_filters_in_format() {
+        init();
+        unsafe { env::set_var("TEST_PRINT_TEMPLATE", "it's {name}") };
+        let mut buffer = vec![];
+        print_env(
+            &PrintArgs {
+                format: Some("export {name}={value|shell}".to_owned()),
+                filter: FilterArgs {
+                    globs: vec!["TEST_PRINT_TEMPLATE".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            &Redactor::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "export TEST_PRINT_TEMPLATE='it'\\''s {name}'\n"
+        );
+        unsafe { env::remove_var("TEST_PRINT_TEMPLATE") };
+    }
+
+    #[test]
+    fn test_print_env_json() {
+        init();
+        unsafe { env::set_var("TEST_PRINT_JSON", "line1\nline2") };
+        let mut buffer = vec![];
+        print_env(
+            &PrintArgs {
+                output: Some(OutputFormat::Json),
+                ..Default::default()
+            },
+            &Redactor::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        let parsed: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed["TEST_PRINT_JSON"], "line1\nline2");
+        unsafe { env::remove_var("TEST_PRINT_JSON") };
+    }
+
+    #[test]
+    fn test_run_command_delete() {
+        init();
+        unsafe { env::set_var("TEST_DELETE_RUN", "test_value") };
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Delete(DeleteArgs {
+                keys: vec!["TEST_DELETE_RUN".to_string()],
+                global: false,
+                dry_run: false,
+                reveal: false,
+                process: vec![],
+                run: RunArgs::default(),
+            }),
+            None,
+            &mut buffer,
+        );
+
+        assert_eq!(env::var("TEST_DELETE_RUN").unwrap(), "test_value");
+        unsafe { env::remove_var("TEST_DELETE_RUN") };
+    }
+
+    #[test]
+    fn test_run_command_load() {
+        init();
+        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(temp_file, "TEST_LOAD_RUN=test_value").unwrap();
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Load(LoadArgs {
+                file: vec![temp_file.path().to_string_lossy().to_string()],
+                cascade: None,
+                explain: false,
+                env_string: vec![],
+                format: None,
+                global: false,
+                dry_run: false,
+                reveal: false,
+                process: vec![],
+                run: RunArgs::default(),
+            }),
+            None,
+            &mut buffer,
+        );
+
+        assert!(env::var("TEST_LOAD_RUN").is_err());
+    }
+
+    #[test]
+    fn test_print_env_command() {
+        init();
+        // Set test variable
+        unsafe { env::set_var("TEST_PRINT_VAR", "test_value") };
+
+        let mut buffer = vec![];
+        print_env(&PrintArgs::default(), &Redactor::default(), &mut buffer).unwrap();
+        assert!(
+            String::from_utf8(buffer)
                 .unwrap()
                 .contains("TEST_PRINT_VAR = \"test_value\"")
         );
@@ -482,7 +1555,7 @@ mod tests {
         unsafe { env::set_var("TEST_VAR_2", "value2") };
 
         let mut buffer = vec![];
-        print_env(&PrintArgs { format: None }, &mut buffer);
+        print_env(&PrintArgs::default(), &Redactor::default(), &mut buffer).unwrap();
         assert!(
             String::from_utf8(buffer.clone())
                 .unwrap()
@@ -505,13 +1578,14 @@ mod tests {
         unsafe { env::set_var("TEST_GET_VAR", "test_value") };
 
         let args = GetArgs {
-            key: "TEST_GET_VAR".to_string(),
+            keys: vec!["TEST_GET_VAR".to_string()],
             no_similar_names: false,
+            ..Default::default()
         };
         let mut buffer = vec![];
 
-        let result = get(&args, &mut buffer);
-        assert!(result.is_ok());
+        let errors = get(&args, &Redactor::default(), &mut buffer);
+        assert!(errors.is_empty());
         assert!(
             String::from_utf8(buffer)
                 .unwrap()
@@ -521,20 +1595,43 @@ mod tests {
         unsafe { env::remove_var("TEST_GET_VAR") };
     }
 
+    #[test]
+    fn test_get_secret_variable() {
+        init();
+        unsafe { env::set_var("TEST_GET_SECRET", "hunter2") };
+
+        let mut args = GetArgs {
+            keys: vec!["TEST_GET_SECRET".to_string()],
+            no_similar_names: false,
+            ..Default::default()
+        };
+        let mut buffer = vec![];
+        assert!(get(&args, &Redactor::default(), &mut buffer).is_empty());
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\"********\"\n");
+
+        args.reveal = true;
+        let mut buffer = vec![];
+        run_command(&Commands::Get(args), None, &mut buffer);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\"hunter2\"\n");
+
+        unsafe { env::remove_var("TEST_GET_SECRET") };
+    }
+
     #[test]
     fn test_get_nonexistent_variable_with_similar_names() {
         init();
         unsafe { env::set_var("TEST_SIMILAR", "value") };
 
         let args = GetArgs {
-            key: "TEST_SMILAR".to_string(), // Intentional typo
+            keys: vec!["TEST_SMILAR".to_string()], // Intentional typo
             no_similar_names: false,
+            ..Default::default()
         };
 
         let mut buffer = vec![];
-        let result = get(&args, &mut buffer);
-        assert!(result.is_err());
-        match result.unwrap_err() {
+        let mut errors = get(&args, &Redactor::default(), &mut buffer);
+        assert_eq!(errors.len(), 1);
+        match errors.remove(0) {
             ErrorKind::CannotFindVariable(var, no_similar) => {
                 assert_eq!(var, "TEST_SMILAR");
                 assert!(!no_similar);
@@ -549,14 +1646,15 @@ mod tests {
     fn test_get_nonexistent_variable_no_similar_names() {
         init();
         let args = GetArgs {
-            key: "NONEXISTENT_VAR".to_string(),
+            keys: vec!["NONEXISTENT_VAR".to_string()],
             no_similar_names: true,
+            ..Default::default()
         };
 
         let mut buffer = vec![];
-        let result = get(&args, &mut buffer);
-        assert!(result.is_err());
-        match result.unwrap_err() {
+        let mut errors = get(&args, &Redactor::default(), &mut buffer);
+        assert_eq!(errors.len(), 1);
+        match errors.remove(0) {
             ErrorKind::CannotFindVariable(var, no_similar) => {
                 assert_eq!(var, "NONEXISTENT_VAR");
                 assert!(no_similar);
@@ -566,52 +1664,842 @@ mod tests {
     }
 
     #[test]
-    fn test_get_special_characters() {
+    fn test_get_special_characters() {
+        init();
+        unsafe { env::set_var("TEST_SPECIAL_$#@", "special_value") };
+
+        let args = GetArgs {
+            keys: vec!["TEST_SPECIAL_$#@".to_string()],
+            no_similar_names: false,
+            ..Default::default()
+        };
+
+        let mut buffer = vec![];
+        let errors = get(&args, &Redactor::default(), &mut buffer);
+        assert!(errors.is_empty());
+
+        unsafe { env::remove_var("TEST_SPECIAL_$#@") };
+    }
+
+    #[test]
+    fn test_get_multiple_variables() {
+        init();
+        unsafe { env::set_var("TEST_GET_MULTI_A", "a b") };
+        unsafe { env::set_var("TEST_GET_MULTI_B", "b") };
+
+        let mut args = GetArgs {
+            keys: vec![
+                "TEST_GET_MULTI_A".to_string(),
+                "TEST_GET_MULTI_MISSING".to_string(),
+                "TEST_GET_MULTI_B".to_string(),
+            ],
+            ..Default::default()
+        };
+        let mut buffer = vec![];
+        let errors = get(&args, &Redactor::default(), &mut buffer);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            matches!(&errors[0], ErrorKind::CannotFindVariable(key, _) if key == "TEST_GET_MULTI_MISSING")
+        );
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\"a b\"\n\"b\"\n");
+
+        args.default = Some("none".to_string());
+        args.raw = true;
+        let mut buffer = vec![];
+        assert!(get(&args, &Redactor::default(), &mut buffer).is_empty());
+        assert_eq!(String::from_utf8(buffer).unwrap(), "a b\nnone\nb\n");
+
+        unsafe { env::remove_var("TEST_GET_MULTI_A") };
+        unsafe { env::remove_var("TEST_GET_MULTI_B") };
+    }
+
+    #[test]
+    fn test_get_null_separated() {
+        init();
+        unsafe { env::set_var("TEST_GET_NULL", "line1\nline2") };
+
+        let args = GetArgs {
+            keys: vec!["TEST_GET_NULL".to_string(), "TEST_GET_NULL".to_string()],
+            null: true,
+            ..Default::default()
+        };
+        let mut buffer = vec![];
+        assert!(get(&args, &Redactor::default(), &mut buffer).is_empty());
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "line1\nline2\0line1\nline2\0"
+        );
+
+        unsafe { env::remove_var("TEST_GET_NULL") };
+    }
+
+    #[test]
+    fn test_get_raw_keeps_positions_of_missing() {
+        init();
+        unsafe { env::set_var("TEST_GET_POSITION", "value") };
+
+        let mut args = GetArgs {
+            keys: vec![
+                "TEST_GET_POSITION_MISSING".to_string(),
+                "TEST_GET_POSITION".to_string(),
+            ],
+            raw: true,
+            ..Default::default()
+        };
+        let mut buffer = vec![];
+        assert_eq!(get(&args, &Redactor::default(), &mut buffer).len(), 1);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\nvalue\n");
+
+        args.raw = false;
+        args.null = true;
+        let mut buffer = vec![];
+        assert_eq!(get(&args, &Redactor::default(), &mut buffer).len(), 1);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\0value\0");
+
+        args.null = false;
+        let mut buffer = vec![];
+        assert_eq!(get(&args, &Redactor::default(), &mut buffer).len(), 1);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\"value\"\n");
+
+        unsafe { env::remove_var("TEST_GET_POSITION") };
+    }
+
+    #[test]
+    fn test_run_command_get_exit_codes() {
+        init();
+        unsafe { env::set_var("TEST_GET_EXIT", "value") };
+
+        let mut args = GetArgs {
+            keys: vec![
+                "TEST_GET_EXIT".to_string(),
+                "TEST_GET_EXIT_MISSING".to_string(),
+            ],
+            no_similar_names: true,
+            ..Default::default()
+        };
+        // Some variables are missing
+        assert_eq!(
+            run_command(&Commands::Get(args.clone()), None, &mut vec![]),
+            ExitCode::from(2)
+        );
+        args.allow_missing = true;
+        assert_eq!(
+            run_command(&Commands::Get(args.clone()), None, &mut vec![]),
+            ExitCode::SUCCESS
+        );
+        // All variables are missing
+        args.keys.remove(0);
+        assert_eq!(
+            run_command(&Commands::Get(args), None, &mut vec![]),
+            ExitCode::FAILURE
+        );
+
+        unsafe { env::remove_var("TEST_GET_EXIT") };
+    }
+
+    fn path_value(paths: &[&str]) -> String {
+        env::join_paths(paths).unwrap().into_string().unwrap()
+    }
+
+    /// Options for showing changes without masking secrets
+    fn unmasked() -> DiffOptions {
+        DiffOptions {
+            redactor: Redactor::disabled(),
+            color: false,
+        }
+    }
+
+    /// Command for child process, which succeeds only if variable has given value
+    #[cfg(not(windows))]
+    fn expect_value(key: &str, value: &str) -> Vec<String> {
+        vec![format!("test \"${}\" = '{}'", key, value)]
+    }
+
+    fn path_edit_args(key: &str, paths: &[&str]) -> PathEditArgs {
+        PathEditArgs {
+            key: key.to_string(),
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        }
+    }
+
+    #[test]
+    fn test_path_list() {
+        init();
+        unsafe { env::set_var("TEST_PATH_LIST", path_value(&["/a", "", "/b"])) };
+        let mut buffer = vec![];
+        let result = path(
+            &PathCommands::List(PathVarArgs {
+                key: "TEST_PATH_LIST".to_string(),
+            }),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        );
+        assert!(matches!(result, Ok(None)));
+        assert_eq!(String::from_utf8(buffer).unwrap(), "/a\n/b\n");
+        unsafe { env::remove_var("TEST_PATH_LIST") };
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_path_append_and_prepend() {
+        init();
+        unsafe { env::set_var("TEST_PATH_EDIT", path_value(&["/a", "/b"])) };
+        let mut args = path_edit_args("TEST_PATH_EDIT", &["/a", "/c"]);
+        args.process = expect_value("TEST_PATH_EDIT", &path_value(&["/b", "/a", "/c"]));
+        let result = path(
+            &PathCommands::Append(args),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        assert_eq!(
+            env::var("TEST_PATH_EDIT").unwrap(),
+            path_value(&["/a", "/b"])
+        );
+
+        unsafe { env::set_var("TEST_PATH_EDIT", path_value(&["/b", "/a", "/c"])) };
+        let mut args = path_edit_args("TEST_PATH_EDIT", &["/c"]);
+        args.process = expect_value("TEST_PATH_EDIT", &path_value(&["/c", "/b", "/a"]));
+        let result = path(
+            &PathCommands::Prepend(args),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        unsafe { env::remove_var("TEST_PATH_EDIT") };
+    }
+
+    #[test]
+    fn test_path_dry_run() {
+        unsafe { env::set_var("TEST_PATH_DRY_RUN", path_value(&["/a", "/a"])) };
+        let args = PathDedupeArgs {
+            key: "TEST_PATH_DRY_RUN".to_string(),
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        path(
+            &PathCommands::Dedupe(args.clone()),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            format!(
+                "~ TEST_PATH_DRY_RUN = {:?} → \"/a\"\n",
+                path_value(&["/a", "/a"])
+            )
+        );
+        assert_eq!(
+            env::var("TEST_PATH_DRY_RUN").unwrap(),
+            path_value(&["/a", "/a"])
+        );
+
+        // Nothing changes if list has no duplicates
+        unsafe { env::set_var("TEST_PATH_DRY_RUN", "/a") };
+        let mut buffer = vec![];
+        path(
+            &PathCommands::Dedupe(args),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "No changes\n");
+        unsafe { env::remove_var("TEST_PATH_DRY_RUN") };
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_path_append_to_missing_variable() {
+        init();
+        let mut args = path_edit_args("TEST_PATH_NEW", &["/a"]);
+        args.process = expect_value("TEST_PATH_NEW", "/a");
+        let result = path(
+            &PathCommands::Append(args),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        assert!(env::var("TEST_PATH_NEW").is_err());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_path_remove_and_dedupe() {
+        init();
+        unsafe {
+            env::set_var(
+                "TEST_PATH_REMOVE",
+                path_value(&["/a", "/b", "/a", "/c", "/b"]),
+            )
+        };
+        let mut args = path_edit_args("TEST_PATH_REMOVE", &["/c", "/d"]);
+        args.process = expect_value("TEST_PATH_REMOVE", &path_value(&["/a", "/b", "/a", "/b"]));
+        let result = path(
+            &PathCommands::Remove(args),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+
+        let result = path(
+            &PathCommands::Dedupe(PathDedupeArgs {
+                key: "TEST_PATH_REMOVE".to_string(),
+                global: false,
+                dry_run: false,
+                reveal: false,
+                process: expect_value("TEST_PATH_REMOVE", &path_value(&["/a", "/b", "/c"])),
+                run: RunArgs::default(),
+            }),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        assert_eq!(
+            env::var("TEST_PATH_REMOVE").unwrap(),
+            path_value(&["/a", "/b", "/a", "/c", "/b"])
+        );
+        unsafe { env::remove_var("TEST_PATH_REMOVE") };
+    }
+
+    #[test]
+    fn test_path_remove_from_missing_variable() {
+        init();
+        let result = path(
+            &PathCommands::Remove(path_edit_args("TEST_PATH_REMOVE_MISSING", &["/a"])),
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
+        assert!(matches!(result, Err(ErrorKind::CannotFindVariable(_, _))));
+    }
+
+    #[test]
+    fn test_path_check() {
+        init();
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        let missing = dir.path().join("missing");
+        unsafe { env::set_var("TEST_PATH_CHECK", path_value(&[dir_path])) };
+        let args = PathVarArgs {
+            key: "TEST_PATH_CHECK".to_string(),
+        };
+        let mut buffer = vec![];
+        assert!(
+            path(
+                &PathCommands::Check(args),
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                &mut buffer
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            format!("{}: ok\n", dir_path)
+        );
+
+        unsafe {
+            env::set_var(
+                "TEST_PATH_CHECK",
+                path_value(&[dir_path, missing.to_str().unwrap()]),
+            )
+        };
+        let mut buffer = vec![];
+        assert_eq!(
+            run_command(
+                &Commands::Path(PathArgs {
+                    command: PathCommands::Check(PathVarArgs {
+                        key: "TEST_PATH_CHECK".to_string(),
+                    }),
+                }),
+                None,
+                &mut buffer,
+            ),
+            ExitCode::FAILURE
+        );
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains(&format!("{}: missing", missing.display()))
+        );
+        unsafe { env::remove_var("TEST_PATH_CHECK") };
+    }
+
+    #[test]
+    fn test_parse_assignments() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let pairs = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            parse_assignments(&args(&["A=1", "B=x=y", "C="])).unwrap(),
+            pairs(&[("A", "1"), ("B", "x=y"), ("C", "")])
+        );
+        // Name followed by value
+        assert_eq!(
+            parse_assignments(&args(&["A", "x=y"])).unwrap(),
+            pairs(&[("A", "x=y")])
+        );
+        assert!(matches!(
+            parse_assignments(&args(&["A=1", "B"])),
+            Err(ErrorKind::ParsingError(_))
+        ));
+        assert!(matches!(
+            parse_assignments(&args(&["A"])),
+            Err(ErrorKind::ParsingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_multiple_variables() {
+        let args = SetArgs {
+            variables: vec![
+                "TEST_SET_MULTI_A=1".to_string(),
+                "TEST_SET_MULTI_B=2".to_string(),
+            ],
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        assert!(
+            set(
+                &args,
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                vec![]
+            )
+            .is_ok()
+        );
+        assert!(env::var("TEST_SET_MULTI_A").is_err());
+        assert!(env::var("TEST_SET_MULTI_B").is_err());
+    }
+
+    #[test]
+    fn test_set_strict_names() {
+        let args = SetArgs {
+            variables: vec!["test-set-strict=1".to_string()],
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        assert!(
+            set(
+                &args,
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                vec![]
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            set(
+                &args,
+                NameValidation::Strict,
+                &DiffOptions::default(),
+                vec![]
+            ),
+            Err(ErrorKind::NameValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_command_strict_names_from_config() {
+        let config = Config {
+            name_validation: Some(NameValidation::Strict),
+            ..Default::default()
+        };
+        let command = Commands::Set(SetArgs {
+            variables: vec!["1TEST_STRICT=1".to_string()],
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        });
+        assert_eq!(
+            run_command(&command, Some(config), vec![]),
+            ExitCode::FAILURE
+        );
+        assert_eq!(run_command(&command, None, vec![]), ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn test_read_env_input_strict_names() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, r#"{{"my-var": "value"}}"#).unwrap();
+        let input = EnvInput {
+            format: InputFormat::Json,
+            ..env_file(temp_file.path(), false)
+        };
+        assert!(read_env_input(&input, NameValidation::Permissive, &BTreeMap::new()).is_ok());
+        assert!(matches!(
+            read_env_input(&input, NameValidation::Strict, &BTreeMap::new()),
+            Err(ErrorKind::NameValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_multiple_variables_is_atomic() {
+        let args = SetArgs {
+            variables: vec![
+                "TEST_SET_ATOMIC=1".to_string(),
+                "INVALID NAME=2".to_string(),
+            ],
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        assert!(matches!(
+            set(
+                &args,
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                vec![]
+            ),
+            Err(ErrorKind::NameValidationError(_))
+        ));
+        assert!(env::var("TEST_SET_ATOMIC").is_err());
+    }
+
+    #[test]
+    fn test_set_dry_run() {
+        unsafe { env::set_var("TEST_SET_DRY_RUN_OLD", "old") };
+        let args = SetArgs {
+            variables: vec![
+                "TEST_SET_DRY_RUN_NEW=1".to_string(),
+                "TEST_SET_DRY_RUN_OLD=new".to_string(),
+            ],
+            global: true,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        assert!(
+            set(
+                &args,
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                &mut buffer
+            )
+            .unwrap()
+            .is_none()
+        );
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "+ TEST_SET_DRY_RUN_NEW = \"1\"\n~ TEST_SET_DRY_RUN_OLD = \"old\" → \"new\"\n"
+        );
+        assert!(env::var("TEST_SET_DRY_RUN_NEW").is_err());
+        assert_eq!(env::var("TEST_SET_DRY_RUN_OLD").unwrap(), "old");
+        unsafe { env::remove_var("TEST_SET_DRY_RUN_OLD") };
+    }
+
+    #[test]
+    fn test_set_dry_run_masks_secrets() {
+        let args = SetArgs {
+            variables: vec!["TEST_DRY_RUN_API_TOKEN=hunter2".to_string()],
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        set(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "+ TEST_DRY_RUN_API_TOKEN = \"********\"\n"
+        );
+
+        let mut buffer = vec![];
+        set(&args, NameValidation::Permissive, &unmasked(), &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "+ TEST_DRY_RUN_API_TOKEN = \"hunter2\"\n"
+        );
+    }
+
+    #[test]
+    fn test_add_dry_run() {
+        unsafe { env::set_var("TEST_ADD_DRY_RUN", "a") };
+        let args = AddArgs {
+            key: "TEST_ADD_DRY_RUN".to_string(),
+            value: "b".to_string(),
+            separator: Some(",".to_string()),
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "~ TEST_ADD_DRY_RUN = \"a\" → \"a,b\"\n"
+        );
+        assert_eq!(env::var("TEST_ADD_DRY_RUN").unwrap(), "a");
+        unsafe { env::remove_var("TEST_ADD_DRY_RUN") };
+    }
+
+    #[test]
+    fn test_delete_dry_run() {
+        unsafe { env::set_var("TEST_DELETE_DRY_RUN", "value") };
+        let args = DeleteArgs {
+            keys: vec![
+                "TEST_DELETE_DRY_RUN".to_string(),
+                "TEST_DELETE_DRY_RUN_MISSING".to_string(),
+            ],
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "- TEST_DELETE_DRY_RUN = \"value\"\n"
+        );
+        assert_eq!(env::var("TEST_DELETE_DRY_RUN").unwrap(), "value");
+        unsafe { env::remove_var("TEST_DELETE_DRY_RUN") };
+    }
+
+    #[test]
+    fn test_load_dry_run() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "TEST_LOAD_DRY_RUN=value").unwrap();
+        let args = LoadArgs {
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
+            global: false,
+            dry_run: true,
+            reveal: false,
+            process: vec!["exit 1".to_string()],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        // Process isn't started in dry run
+        assert!(
+            load(
+                &args,
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                &mut buffer
+            )
+            .unwrap()
+            .is_none()
+        );
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "+ TEST_LOAD_DRY_RUN = \"value\"\n"
+        );
+        assert!(env::var("TEST_LOAD_DRY_RUN").is_err());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_delete_multiple_variables() {
+        unsafe { env::set_var("TEST_DELETE_MULTI_A", "1") };
+        unsafe { env::set_var("TEST_DELETE_MULTI_B", "2") };
+        let mut args = DeleteArgs {
+            keys: vec![
+                "TEST_DELETE_MULTI_A".to_string(),
+                "INVALID NAME".to_string(),
+                "TEST_DELETE_MULTI_B".to_string(),
+            ],
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![
+                "test -z \"${TEST_DELETE_MULTI_A+set}${TEST_DELETE_MULTI_B+set}\"".to_string(),
+            ],
+            run: RunArgs::default(),
+        };
+        // Nothing is deleted if any of names is invalid
+        assert!(matches!(
+            delete(
+                &args,
+                NameValidation::Permissive,
+                &DiffOptions::default(),
+                vec![]
+            ),
+            Err(ErrorKind::NameValidationError(_))
+        ));
+        assert_eq!(env::var("TEST_DELETE_MULTI_A").unwrap(), "1");
+
+        args.keys.remove(1);
+        let result = delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        assert_eq!(env::var("TEST_DELETE_MULTI_B").unwrap(), "2");
+        unsafe { env::remove_var("TEST_DELETE_MULTI_A") };
+        unsafe { env::remove_var("TEST_DELETE_MULTI_B") };
+    }
+
+    #[test]
+    fn test_run_command_invalid_secret_patterns() {
+        init();
+        let config = || Config {
+            secret_patterns: Some(vec!["[".to_string()]),
+            ..Default::default()
+        };
+        let mut args = SetArgs {
+            variables: vec!["TEST_SET_INVALID_PATTERNS=1".to_string()],
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        // Patterns are used only for printing changes
+        assert_eq!(
+            run_command(&Commands::Set(args.clone()), Some(config()), &mut vec![]),
+            ExitCode::SUCCESS
+        );
+        args.dry_run = true;
+        assert_eq!(
+            run_command(&Commands::Set(args), Some(config()), &mut vec![]),
+            ExitCode::FAILURE
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_run_command_no_shell_from_config() {
         init();
-        unsafe { env::set_var("TEST_SPECIAL_$#@", "special_value") };
-
-        let args = GetArgs {
-            key: "TEST_SPECIAL_$#@".to_string(),
-            no_similar_names: false,
+        let config = Config {
+            no_shell: Some(true),
+            ..Default::default()
         };
-
-        let mut buffer = vec![];
-        let result = get(&args, &mut buffer);
-        assert!(result.is_ok());
-
-        unsafe { env::remove_var("TEST_SPECIAL_$#@") };
+        let mut args = SetArgs {
+            variables: vec!["TEST_SET_NO_SHELL=1".to_string()],
+            global: false,
+            dry_run: false,
+            reveal: false,
+            run: RunArgs::default(),
+            // With shell it would be "test a b", which is invalid expression
+            process: vec!["test".to_string(), "a b".to_string()],
+        };
+        assert_eq!(
+            run_command(&Commands::Set(args.clone()), Some(config), &mut vec![]),
+            ExitCode::SUCCESS
+        );
+        let config = Config {
+            no_shell: Some(true),
+            ..Default::default()
+        };
+        args.run.shell = true;
+        assert_ne!(
+            run_command(&Commands::Set(args), Some(config), &mut vec![]),
+            ExitCode::SUCCESS
+        );
+        unsafe { env::remove_var("TEST_SET_NO_SHELL") };
     }
 
     #[test]
     fn test_set_valid_variable() {
         let args = SetArgs {
-            key: "TEST_SET_VAR".to_string(),
-            value: "test_value".to_string(),
+            variables: vec!["TEST_SET_VAR".to_string(), "test_value".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = set(&args);
+        let result = set(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok());
 
-        assert_eq!(env::var("TEST_SET_VAR").unwrap(), "test_value");
-        unsafe { env::remove_var("TEST_SET_VAR") };
+        assert!(env::var("TEST_SET_VAR").is_err());
     }
 
     #[test]
     fn test_set_invalid_variable_name() {
         let args = SetArgs {
-            key: "INVALID NAME".to_string(), // Space in name
-            value: "test_value".to_string(),
+            variables: vec!["INVALID NAME".to_string(), "test_value".to_string()], // Space in name
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = set(&args);
+        let result = set(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         match result.unwrap_err() {
             ErrorKind::NameValidationError(err) => {
-                assert!(err.contains("cannot contain spaces"));
+                assert!(err.contains("Invalid character ' ' at position 8"));
             }
             _ => panic!("Unexpected error type"),
         }
@@ -620,13 +2508,20 @@ mod tests {
     #[test]
     fn test_set_empty_variable_name() {
         let args = SetArgs {
-            key: "".to_string(),
-            value: "test_value".to_string(),
+            variables: vec!["".to_string(), "test_value".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = set(&args);
+        let result = set(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         match result.unwrap_err() {
             ErrorKind::NameValidationError(err) => {
@@ -648,16 +2543,22 @@ mod tests {
         let test_cmd = vec!["echo".to_string(), "test".to_string()];
 
         let args = SetArgs {
-            key: "TEST_PROCESS_VAR".to_string(),
-            value: "test_value".to_string(),
+            variables: vec!["TEST_PROCESS_VAR".to_string(), "test_value".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: test_cmd,
+            run: RunArgs::default(),
         };
 
-        let result = set(&args);
+        let result = set(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok(), "Expected Ok result, got {:?}", result);
-        assert_eq!(env::var("TEST_PROCESS_VAR").unwrap(), "test_value");
-        unsafe { env::remove_var("TEST_PROCESS_VAR") };
+        assert!(env::var("TEST_PROCESS_VAR").is_err());
     }
 
     #[test]
@@ -665,16 +2566,23 @@ mod tests {
         unsafe { env::set_var("TEST_OVERWRITE", "old_value") };
 
         let args = SetArgs {
-            key: "TEST_OVERWRITE".to_string(),
-            value: "new_value".to_string(),
+            variables: vec!["TEST_OVERWRITE".to_string(), "new_value".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = set(&args);
+        let result = set(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok());
 
-        assert_eq!(env::var("TEST_OVERWRITE").unwrap(), "new_value");
+        assert_eq!(env::var("TEST_OVERWRITE").unwrap(), "old_value");
         unsafe { env::remove_var("TEST_OVERWRITE") };
     }
 
@@ -683,31 +2591,51 @@ mod tests {
         let args = AddArgs {
             key: "TEST_ADD_NEW".to_string(),
             value: "new_value".to_string(),
+            separator: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = add(&args);
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok());
-        assert_eq!(env::var("TEST_ADD_NEW").unwrap(), "new_value");
-        unsafe { env::remove_var("TEST_ADD_NEW") };
+        assert!(env::var("TEST_ADD_NEW").is_err());
     }
 
     #[test]
+    #[cfg(not(windows))]
     fn test_add_to_existing_variable() {
-        unsafe { env::set_var("TEST_ADD_EXISTING", "existing_") };
+        unsafe { env::set_var("TEST_ADD_EXISTING_CHILD", "existing_") };
 
         let args = AddArgs {
-            key: "TEST_ADD_EXISTING".to_string(),
+            key: "TEST_ADD_EXISTING_CHILD".to_string(),
             value: "appended".to_string(),
+            separator: None,
             global: false,
-            process: vec![],
+            dry_run: false,
+            reveal: false,
+            process: expect_value("TEST_ADD_EXISTING_CHILD", "existing_appended"),
+            run: RunArgs::default(),
         };
 
-        let result = add(&args);
-        assert!(result.is_ok());
-        assert_eq!(env::var("TEST_ADD_EXISTING").unwrap(), "existing_appended");
-        unsafe { env::remove_var("TEST_ADD_EXISTING") };
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        // Only child process gets changed value
+        assert_eq!(env::var("TEST_ADD_EXISTING_CHILD").unwrap(), "existing_");
+        unsafe { env::remove_var("TEST_ADD_EXISTING_CHILD") };
     }
 
     #[test]
@@ -715,15 +2643,24 @@ mod tests {
         let args = AddArgs {
             key: "INVALID NAME".to_string(),
             value: "test_value".to_string(),
+            separator: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = add(&args);
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         match result.unwrap_err() {
             ErrorKind::NameValidationError(err) => {
-                assert!(err.contains("cannot contain spaces"));
+                assert!(err.contains("Invalid character ' ' at position 8"));
             }
             _ => panic!("Unexpected error type"),
         }
@@ -736,11 +2673,20 @@ mod tests {
         let args = AddArgs {
             key: "TEST_ADD_EMPTY".to_string(),
             value: "".to_string(),
+            separator: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = add(&args);
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok());
         assert_eq!(env::var("TEST_ADD_EMPTY").unwrap(), "existing");
         unsafe { env::remove_var("TEST_ADD_EMPTY") };
@@ -757,41 +2703,164 @@ mod tests {
         let args = AddArgs {
             key: "TEST_ADD_PROCESS".to_string(),
             value: "_value".to_string(),
+            separator: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: test_cmd,
+            run: RunArgs::default(),
         };
 
         unsafe { env::set_var("TEST_ADD_PROCESS", "initial") };
-        let result = add(&args);
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok(), "Expected Ok result, got {:?}", result);
-        assert_eq!(env::var("TEST_ADD_PROCESS").unwrap(), "initial_value");
+        assert_eq!(env::var("TEST_ADD_PROCESS").unwrap(), "initial");
         unsafe { env::remove_var("TEST_ADD_PROCESS") };
     }
 
+    #[test]
+    #[cfg(not(windows))]
+    fn test_add_with_separator() {
+        let mut args = AddArgs {
+            key: "TEST_ADD_SEPARATOR".to_string(),
+            value: "/opt/lib".to_string(),
+            separator: Some(":".to_string()),
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: expect_value("TEST_ADD_SEPARATOR", "/opt/lib"),
+            run: RunArgs::default(),
+        };
+        // Separator isn't added to empty variable
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+
+        unsafe { env::set_var("TEST_ADD_SEPARATOR", "/opt/lib") };
+        args.value = "/usr/lib".to_string();
+        args.process = expect_value("TEST_ADD_SEPARATOR", "/opt/lib:/usr/lib");
+        let result = add(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        unsafe { env::remove_var("TEST_ADD_SEPARATOR") };
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_prepend() {
+        unsafe { env::set_var("TEST_PREPEND", "value") };
+        let mut args = AddArgs {
+            key: "TEST_PREPEND".to_string(),
+            value: "start_".to_string(),
+            separator: None,
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: expect_value("TEST_PREPEND", "start_value"),
+            run: RunArgs::default(),
+        };
+        let result = prepend(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        assert_eq!(env::var("TEST_PREPEND").unwrap(), "value");
+
+        unsafe { env::set_var("TEST_PREPEND", "start_value") };
+        args.value = "-Dwarnings".to_string();
+        args.separator = Some(" ".to_string());
+        args.process = expect_value("TEST_PREPEND", "-Dwarnings start_value");
+        let result = prepend(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        unsafe { env::remove_var("TEST_PREPEND") };
+    }
+
+    #[test]
+    fn test_run_command_prepend_invalid_name() {
+        assert_eq!(
+            run_command(
+                &Commands::Prepend(AddArgs {
+                    key: "INVALID NAME".to_string(),
+                    value: "value".to_string(),
+                    separator: None,
+                    global: false,
+                    dry_run: false,
+                    reveal: false,
+                    process: vec![],
+                    run: RunArgs::default(),
+                }),
+                None,
+                &mut vec![],
+            ),
+            ExitCode::FAILURE
+        );
+    }
+
     #[test]
     fn test_delete_existing_variable() {
         unsafe { env::set_var("TEST_DELETE_VAR", "test_value") };
 
         let args = DeleteArgs {
-            key: "TEST_DELETE_VAR".to_string(),
+            keys: vec!["TEST_DELETE_VAR".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = delete(&args);
+        let result = delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok());
-        assert!(env::var("TEST_DELETE_VAR").is_err());
+        assert_eq!(env::var("TEST_DELETE_VAR").unwrap(), "test_value");
+        unsafe { env::remove_var("TEST_DELETE_VAR") };
     }
 
     #[test]
     fn test_delete_nonexistent_variable() {
         let args = DeleteArgs {
-            key: "NONEXISTENT_VAR".to_string(),
+            keys: vec!["NONEXISTENT_VAR".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = delete(&args);
+        let result = delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         // Should succeed even if variable doesn't exist
         assert!(result.is_ok());
     }
@@ -799,16 +2868,24 @@ mod tests {
     #[test]
     fn test_delete_with_invalid_name() {
         let args = DeleteArgs {
-            key: "INVALID NAME".to_string(),
+            keys: vec!["INVALID NAME".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = delete(&args);
+        let result = delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         match result.unwrap_err() {
             ErrorKind::NameValidationError(err) => {
-                assert!(err.contains("cannot contain spaces"));
+                assert!(err.contains("Invalid character ' ' at position 8"));
             }
             _ => panic!("Unexpected error type"),
         }
@@ -825,175 +2902,564 @@ mod tests {
         let test_cmd = vec!["echo".to_string(), "test".to_string()];
 
         let args = DeleteArgs {
-            key: "TEST_DELETE_PROCESS".to_string(),
+            keys: vec!["TEST_DELETE_PROCESS".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: test_cmd,
+            run: RunArgs::default(),
         };
 
-        let result = delete(&args);
+        let result = delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok(), "Expected Ok result, got {:?}", result);
-        assert!(env::var("TEST_DELETE_PROCESS").is_err());
+        assert_eq!(env::var("TEST_DELETE_PROCESS").unwrap(), "test_value");
+        unsafe { env::remove_var("TEST_DELETE_PROCESS") };
     }
 
     #[test]
     fn test_delete_with_empty_name() {
         let args = DeleteArgs {
-            key: "".to_string(),
+            keys: vec!["".to_string()],
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
+        };
+
+        let result = delete(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            ErrorKind::NameValidationError(err) => {
+                assert!(err.contains("empty"));
+            }
+            _ => panic!("Unexpected error type"),
+        }
+    }
+
+    #[test]
+    fn test_export_valid_keys() {
+        init();
+        unsafe {
+            env::set_var("TEST_EXPORT_ONE", "val1");
+            env::set_var("TEST_EXPORT_TWO", "val2");
+        }
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = temp_file.path().to_string_lossy().to_string();
+
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Export(ExportArgs {
+                file_name: file_name.clone(),
+                keys: vec!["TEST_EXPORT_ONE".to_string(), "TEST_EXPORT_TWO".to_string()],
+                placeholders: false,
+                ..Default::default()
+            }),
+            None,
+            &mut buffer,
+        );
+
+        let content = std::fs::read_to_string(&file_name).unwrap();
+        assert!(content.contains("TEST_EXPORT_ONE=val1"));
+        assert!(content.contains("TEST_EXPORT_TWO=val2"));
+
+        unsafe {
+            env::remove_var("TEST_EXPORT_ONE");
+            env::remove_var("TEST_EXPORT_TWO");
+        }
+        std::fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_skips_duplicate_keys() {
+        init();
+        unsafe {
+            env::set_var("TEST_EXPORT_ONE", "val");
+        }
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = temp_file.path().to_string_lossy().to_string();
+
+        let args = ExportArgs {
+            file_name: file_name.clone(),
+            keys: vec!["TEST_EXPORT_ONE".to_string(), "TEST_EXPORT_ONE".to_string()],
+            placeholders: false,
+            ..Default::default()
+        };
+
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(result.is_ok());
+
+        let content = std::fs::read_to_string(&file_name).unwrap();
+        assert!(!content.contains(
+            r#"TEST_EXPORT_ONE=val
+TEST_EXPORT_ONE=val"#
+        ));
+
+        unsafe {
+            env::remove_var("TEST_EXPORT_ONE");
+        }
+        std::fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_skips_missing_vars() {
+        init();
+        unsafe {
+            env::set_var("TEST_EXPORT_EXISTING", "val");
+            env::set_var("TEST_EXPORT_EXISTING2", "val2");
+        }
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = temp_file.path().to_string_lossy().to_string();
+
+        let args = ExportArgs {
+            file_name: file_name.clone(),
+            keys: vec![
+                "TEST_EXPORT_EXISTING".to_string(),
+                "TEST_EXPORT_MISSING".to_string(),
+                "TEST_EXPORT_EXISTING2".to_string(),
+            ],
+            placeholders: false,
+            ..Default::default()
+        };
+
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(result.is_ok());
+
+        let content = std::fs::read_to_string(&file_name).unwrap();
+        assert!(content.contains("TEST_EXPORT_EXISTING=val"));
+        assert!(!content.contains("TEST_EXPORT_MISSING"));
+        assert!(content.contains("TEST_EXPORT_EXISTING2=val2"));
+
+        unsafe {
+            env::remove_var("TEST_EXPORT_EXISTING");
+            env::remove_var("TEST_EXPORT_EXISTING2");
+        };
+        std::fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_placeholders() {
+        init();
+        unsafe {
+            env::set_var("TEST_EXPORT_PLAIN", "plain");
+            env::set_var("TEST_EXPORT_API_TOKEN", "secret");
+        }
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = temp_file.path().to_string_lossy().to_string();
+
+        let mut buffer = vec![];
+        run_command(
+            &Commands::Export(ExportArgs {
+                file_name: file_name.clone(),
+                keys: vec![
+                    "TEST_EXPORT_PLAIN".to_string(),
+                    "TEST_EXPORT_API_TOKEN".to_string(),
+                ],
+                placeholders: true,
+                ..Default::default()
+            }),
+            None,
+            &mut buffer,
+        );
+
+        let content = std::fs::read_to_string(&file_name).unwrap();
+        assert!(content.contains("TEST_EXPORT_PLAIN=plain"));
+        assert!(content.contains("TEST_EXPORT_API_TOKEN='********'"));
+        assert!(!content.contains("secret"));
+
+        unsafe {
+            env::remove_var("TEST_EXPORT_PLAIN");
+            env::remove_var("TEST_EXPORT_API_TOKEN");
+        }
+        std::fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_empty_key() {
+        init();
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = temp_file.path().to_string_lossy().to_string();
+
+        let args = ExportArgs {
+            file_name: file_name.clone(),
+            keys: vec!["".to_string()],
+            placeholders: false,
+            ..Default::default()
+        };
+
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            ErrorKind::NameValidationError(e) => {
+                assert!(e.contains("cannot be empty"));
+            }
+            _ => panic!("Unexpected error type"),
+        }
+
+        std::fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_invalid_key_name() {
+        init();
+
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let file_name = temp_file.path().to_string_lossy().to_string();
+
+        let args = ExportArgs {
+            file_name: file_name.clone(),
+            keys: vec!["INVALID KEY".to_string()],
+            placeholders: false,
+            ..Default::default()
+        };
+
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            ErrorKind::NameValidationError(e) => {
+                assert!(e.contains("Invalid character ' ' at position 8"));
+            }
+            _ => panic!("Unexpected error type"),
+        }
+
+        std::fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn test_export_to_buffer() {
+        unsafe { env::set_var("TEST_EXPORT_STDOUT", "a b") };
+
+        let args = ExportArgs {
+            file_name: "-".to_string(),
+            keys: vec!["TEST_EXPORT_STDOUT".to_string()],
+            placeholders: false,
+            output: Some(ExportFormat::Shell),
+            ..Default::default()
         };
+        let mut buffer = vec![];
+        export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut buffer,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "export TEST_EXPORT_STDOUT='a b'\n"
+        );
 
-        let result = delete(&args);
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            ErrorKind::NameValidationError(err) => {
-                assert!(err.contains("empty"));
-            }
-            _ => panic!("Unexpected error type"),
-        }
+        unsafe { env::remove_var("TEST_EXPORT_STDOUT") };
     }
 
     #[test]
-    fn test_export_valid_keys() {
-        init();
+    fn test_export_patterns() {
         unsafe {
-            env::set_var("TEST_EXPORT_ONE", "val1");
-            env::set_var("TEST_EXPORT_TWO", "val2");
+            env::set_var("TEST_EXPORT_PATTERN_B", "b");
+            env::set_var("TEST_EXPORT_PATTERN_A", "a");
+            env::set_var("TEST_EXPORT_PATTERN_SKIP", "skip");
+            env::set_var("TEST_EXPORT_PATTERN_EXTRA", "extra");
         }
 
         let temp_file = tempfile::NamedTempFile::new().unwrap();
         let file_name = temp_file.path().to_string_lossy().to_string();
-
+        let args = ExportArgs {
+            file_name: file_name.clone(),
+            keys: vec!["TEST_EXPORT_PATTERN_EXTRA".to_string()],
+            globs: vec!["TEST_EXPORT_PATTERN_*".to_string()],
+            regexes: vec!["^TEST_EXPORT_PATTERN_NONE$".to_string()],
+            excludes: vec!["*_SKIP".to_string()],
+            ..Default::default()
+        };
         let mut buffer = vec![];
-        run_command(
-            &Commands::Export(ExportArgs {
-                file_name: file_name.clone(),
-                keys: vec!["TEST_EXPORT_ONE".to_string(), "TEST_EXPORT_TWO".to_string()],
-            }),
-            None,
+        export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
             &mut buffer,
-        );
+        )
+        .unwrap();
 
-        let content = std::fs::read_to_string(&format!("{}.env", file_name)).unwrap();
-        assert!(content.contains("TEST_EXPORT_ONE=val1"));
-        assert!(content.contains("TEST_EXPORT_TWO=val2"));
+        assert_eq!(
+            std::fs::read_to_string(&file_name).unwrap(),
+            "TEST_EXPORT_PATTERN_EXTRA=extra\nTEST_EXPORT_PATTERN_A=a\nTEST_EXPORT_PATTERN_B=b\n"
+        );
+        // Matched variables are reported to stderr, not to output
+        assert!(buffer.is_empty());
 
         unsafe {
-            env::remove_var("TEST_EXPORT_ONE");
-            env::remove_var("TEST_EXPORT_TWO");
+            env::remove_var("TEST_EXPORT_PATTERN_B");
+            env::remove_var("TEST_EXPORT_PATTERN_A");
+            env::remove_var("TEST_EXPORT_PATTERN_SKIP");
+            env::remove_var("TEST_EXPORT_PATTERN_EXTRA");
         }
-        std::fs::remove_file(file_name).unwrap();
     }
 
-        #[test]
-    fn test_export_skips_duplicate_keys() {
-        init();
-        unsafe {
-            env::set_var("TEST_EXPORT_ONE", "val");
-        }
-
+    #[test]
+    fn test_export_invalid_pattern() {
         let temp_file = tempfile::NamedTempFile::new().unwrap();
         let file_name = temp_file.path().to_string_lossy().to_string();
+        std::fs::write(&file_name, "KEEP=1\n").unwrap();
 
         let args = ExportArgs {
             file_name: file_name.clone(),
-            keys: vec!["TEST_EXPORT_ONE".to_string(), "TEST_EXPORT_ONE".to_string()],
+            all: true,
+            excludes: vec!["[A".to_string()],
+            ..Default::default()
         };
-
-        let result = export(&args);
-        assert!(result.is_ok());
-
-        let content = std::fs::read_to_string(&format!("{}.env", file_name)).unwrap();
-        assert!(!content.contains(r#"TEST_EXPORT_ONE=val
-TEST_EXPORT_ONE=val"#));
-
-        unsafe {
-            env::remove_var("TEST_EXPORT_ONE");
-        }
-        std::fs::remove_file(file_name).unwrap();
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(matches!(result, Err(ErrorKind::PatternError(_))));
+        // File isn't touched if pattern is invalid
+        assert_eq!(std::fs::read_to_string(&file_name).unwrap(), "KEEP=1\n");
     }
 
     #[test]
-    fn test_export_skips_missing_vars() {
-        init();
-        unsafe {
-            env::set_var("TEST_EXPORT_EXISTING", "val");
-            env::set_var("TEST_EXPORT_EXISTING2", "val2");
-        }
+    fn test_export_merge() {
+        unsafe { env::set_var("TEST_EXPORT_MERGE", "new") };
 
         let temp_file = tempfile::NamedTempFile::new().unwrap();
         let file_name = temp_file.path().to_string_lossy().to_string();
+        std::fs::write(&file_name, "# kept\nTEST_EXPORT_MERGE=old\nOTHER=1\n").unwrap();
 
         let args = ExportArgs {
             file_name: file_name.clone(),
-            keys: vec![
-                "TEST_EXPORT_EXISTING".to_string(),
-                "TEST_EXPORT_MISSING".to_string(),
-                "TEST_EXPORT_EXISTING2".to_string(),
-            ],
+            keys: vec!["TEST_EXPORT_MERGE".to_string()],
+            merge: true,
+            output: Some(ExportFormat::Dotenv),
+            ..Default::default()
         };
+        export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_name).unwrap(),
+            "# kept\nTEST_EXPORT_MERGE=new\nOTHER=1\n"
+        );
 
-        let result = export(&args);
-        assert!(result.is_ok());
-
-        let content = std::fs::read_to_string(&format!("{}.env", file_name)).unwrap();
-        assert!(content.contains("TEST_EXPORT_EXISTING=val"));
-        assert!(!content.contains("TEST_EXPORT_MISSING"));
-        assert!(content.contains("TEST_EXPORT_EXISTING2=val2"));
-
-        unsafe {
-            env::remove_var("TEST_EXPORT_EXISTING");
-            env::remove_var("TEST_EXPORT_EXISTING2");
+        let args = ExportArgs {
+            output: Some(ExportFormat::Json),
+            ..args
         };
-        std::fs::remove_file(file_name).unwrap();
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(matches!(result, Err(ErrorKind::FileError(_))));
+
+        unsafe { env::remove_var("TEST_EXPORT_MERGE") };
     }
 
     #[test]
-    fn test_export_empty_key() {
-        init();
+    fn test_export_no_clobber() {
+        unsafe { env::set_var("TEST_EXPORT_NO_CLOBBER", "new") };
 
         let temp_file = tempfile::NamedTempFile::new().unwrap();
         let file_name = temp_file.path().to_string_lossy().to_string();
+        std::fs::write(&file_name, "KEEP=1\n").unwrap();
 
         let args = ExportArgs {
             file_name: file_name.clone(),
-            keys: vec!["".to_string()],
+            keys: vec!["TEST_EXPORT_NO_CLOBBER".to_string()],
+            no_clobber: true,
+            ..Default::default()
         };
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
+        assert!(matches!(result, Err(ErrorKind::FileError(err)) if err.contains("already exists")));
+        assert_eq!(std::fs::read_to_string(&file_name).unwrap(), "KEEP=1\n");
 
-        let result = export(&args);
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            ErrorKind::NameValidationError(e) => {
-                assert!(e.contains("cannot be empty"));
-            }
-            _ => panic!("Unexpected error type"),
-        }
+        unsafe { env::remove_var("TEST_EXPORT_NO_CLOBBER") };
+    }
 
-        std::fs::remove_file(file_name).unwrap();
+    #[test]
+    fn test_snapshot_save_show_list() {
+        unsafe { env::set_var("TEST_SNAPSHOT_SAVED", "value") };
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().to_path_buf());
+
+        let mut buffer = vec![];
+        let save = SnapshotCommands::Save(SnapshotSaveArgs {
+            name: "base".to_string(),
+            force: false,
+        });
+        snapshot(&save, &store, &unmasked(), &mut buffer).unwrap();
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .starts_with("Saved snapshot base with ")
+        );
+        assert!(snapshot(&save, &store, &unmasked(), &mut vec![]).is_err());
+
+        let mut buffer = vec![];
+        let show = SnapshotCommands::Show(SnapshotShowArgs {
+            name: "base".to_string(),
+            output: None,
+            reveal: false,
+        });
+        snapshot(&show, &store, &unmasked(), &mut buffer).unwrap();
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains("TEST_SNAPSHOT_SAVED = \"value\"\n")
+        );
+
+        let mut buffer = vec![];
+        snapshot(&SnapshotCommands::List, &store, &unmasked(), &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("base: "));
+        assert!(output.contains(" variables, saved "));
+
+        unsafe { env::remove_var("TEST_SNAPSHOT_SAVED") };
     }
 
     #[test]
-    fn test_export_invalid_key_name() {
-        init();
+    fn test_snapshot_list_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join("missing"));
+        let mut buffer = vec![];
+        snapshot(&SnapshotCommands::List, &store, &unmasked(), &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "No snapshots saved\n");
+    }
 
-        let temp_file = tempfile::NamedTempFile::new().unwrap();
-        let file_name = temp_file.path().to_string_lossy().to_string();
+    #[test]
+    fn test_snapshot_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().to_path_buf());
+        let saved = |name: &str, variables: &[(&str, &str)]| {
+            let variables = variables
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()));
+            store.save(name, &Snapshot::new(variables), false).unwrap();
+        };
+        saved(
+            "before",
+            &[
+                ("KEPT", "1"),
+                ("CHANGED", "old"),
+                ("REMOVED", "x"),
+                ("API_TOKEN", "a"),
+            ],
+        );
+        saved(
+            "after",
+            &[
+                ("KEPT", "1"),
+                ("CHANGED", "new"),
+                ("ADDED", "y"),
+                ("API_TOKEN", "b"),
+            ],
+        );
 
-        let args = ExportArgs {
-            file_name: file_name.clone(),
-            keys: vec!["INVALID KEY".to_string()],
+        let diff = |reveal: bool| {
+            let mut buffer = vec![];
+            let options = DiffOptions {
+                redactor: Redactor::new(None, reveal).unwrap(),
+                color: false,
+            };
+            let command = SnapshotCommands::Diff(SnapshotDiffArgs {
+                from: "before".to_string(),
+                to: "after".to_string(),
+                reveal,
+            });
+            snapshot(&command, &store, &options, &mut buffer).unwrap();
+            String::from_utf8(buffer).unwrap()
         };
+        assert_eq!(
+            diff(false),
+            "+ ADDED = \"y\"\n~ API_TOKEN = \"********\" → \"********\"\n~ CHANGED = \"old\" → \"new\"\n- REMOVED = \"x\"\n"
+        );
+        assert!(diff(true).contains("~ API_TOKEN = \"a\" → \"b\"\n"));
+    }
 
-        let result = export(&args);
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            ErrorKind::NameValidationError(e) => {
-                assert!(e.contains("cannot contain spaces"));
-            }
-            _ => panic!("Unexpected error type"),
-        }
+    #[test]
+    fn test_snapshot_diff_current() {
+        unsafe { env::set_var("TEST_SNAPSHOT_DIFF_CURRENT", "new") };
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().to_path_buf());
+        let mut variables: BTreeMap<String, String> =
+            variables::get_variables().into_iter().collect();
+        variables.insert("TEST_SNAPSHOT_DIFF_CURRENT".to_string(), "old".to_string());
+        store
+            .save("base", &Snapshot::new(variables), false)
+            .unwrap();
 
-        std::fs::remove_file(file_name).unwrap();
+        let mut buffer = vec![];
+        let command = SnapshotCommands::Diff(SnapshotDiffArgs {
+            from: "base".to_string(),
+            to: "current".to_string(),
+            reveal: false,
+        });
+        snapshot(&command, &store, &unmasked(), &mut buffer).unwrap();
+        assert!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .contains("~ TEST_SNAPSHOT_DIFF_CURRENT = \"old\" → \"new\"\n")
+        );
+
+        unsafe { env::remove_var("TEST_SNAPSHOT_DIFF_CURRENT") };
+    }
+
+    #[test]
+    fn test_snapshot_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().to_path_buf());
+        let command = SnapshotCommands::Restore(SnapshotRestoreArgs {
+            name: "missing".to_string(),
+            run: RunArgs::default(),
+            process: vec!["echo".to_string()],
+        });
+        let result = snapshot(&command, &store, &unmasked(), &mut vec![]);
+        assert!(matches!(result, Err(ErrorKind::SnapshotError(_))));
     }
 
     #[test]
@@ -1003,27 +3469,50 @@ TEST_EXPORT_ONE=val"#));
         let args = ExportArgs {
             file_name: "/test/test/test/test/test/test/test".to_string(),
             keys: vec!["DUMMY".to_string()],
+            placeholders: false,
+            ..Default::default()
         };
 
-        let result = export(&args);
+        let result = export(
+            &args,
+            NameValidation::Permissive,
+            &Redactor::disabled(),
+            &mut vec![],
+        );
         assert!(result.is_err());
     }
 
     #[test]
+    #[cfg(not(windows))]
     fn test_load_valid_env_file() {
         let mut temp_file = NamedTempFile::new().unwrap();
         writeln!(temp_file, "TEST_VAR=test_value\nOTHER_VAR=other_value").unwrap();
 
         let args = LoadArgs {
-            file: temp_file.path().to_string_lossy().to_string(),
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
             global: false,
-            process: vec![],
+            dry_run: false,
+            reveal: false,
+            process: vec![
+                "test \"$TEST_VAR\" = test_value && test \"$OTHER_VAR\" = other_value".to_string(),
+            ],
+            run: RunArgs::default(),
         };
 
-        let result = load(&args);
-        assert!(result.is_ok());
-        assert_eq!(env::var("TEST_VAR").unwrap(), "test_value");
-        assert_eq!(env::var("OTHER_VAR").unwrap(), "other_value");
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        assert!(env::var("TEST_VAR").is_err());
+        assert!(env::var("OTHER_VAR").is_err());
 
         unsafe { env::remove_var("TEST_VAR") };
         unsafe { env::remove_var("OTHER_VAR") };
@@ -1032,12 +3521,24 @@ TEST_EXPORT_ONE=val"#));
     #[test]
     fn test_load_nonexistent_file() {
         let args = LoadArgs {
-            file: "nonexistent.env".to_string(),
+            file: vec!["nonexistent.env".to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = load(&args);
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), ErrorKind::FileError(_)));
     }
@@ -1045,16 +3546,28 @@ TEST_EXPORT_ONE=val"#));
     #[test]
     fn test_load_invalid_env_file() {
         let mut temp_file = NamedTempFile::new().unwrap();
-        // Using invalid .env format that dotenv_parser will reject
+        // Using invalid .env format that parser will reject
         writeln!(temp_file, "TEST_VAR test_value").unwrap();
 
         let args = LoadArgs {
-            file: temp_file.path().to_string_lossy().to_string(),
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = load(&args);
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), ErrorKind::ParsingError(_)));
     }
@@ -1065,32 +3578,378 @@ TEST_EXPORT_ONE=val"#));
         writeln!(temp_file, "TEST_PROCESS_VAR=process_value").unwrap();
 
         #[cfg(windows)]
-        let cmd = vec!["cmd".to_string(), "/C".to_string(), "echo".to_string(), "test".to_string()];
+        let cmd = vec![
+            "cmd".to_string(),
+            "/C".to_string(),
+            "echo".to_string(),
+            "test".to_string(),
+        ];
         #[cfg(not(windows))]
         let cmd = vec!["echo".to_string(), "test".to_string()];
 
         let args = LoadArgs {
-            file: temp_file.path().to_string_lossy().to_string(),
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: cmd,
+            run: RunArgs::default(),
         };
 
         // First verify the variable is set correctly
-        let result = load(&args);
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok(), "Load operation failed: {:?}", result);
     }
 
+    /// Create dotenv file input for given path
+    fn env_file(path: &Path, optional: bool) -> EnvInput {
+        EnvInput {
+            source: EnvSource::File {
+                path: path.to_string_lossy().to_string(),
+                optional,
+            },
+            format: InputFormat::Dotenv,
+        }
+    }
+
+    #[test]
+    fn test_read_env_input() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "B_VAR=2\nA_VAR=1").unwrap();
+
+        let variables = read_env_input(
+            &env_file(temp_file.path(), false),
+            NameValidation::Permissive,
+            &BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            variables.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("A_VAR".to_string(), "1".to_string()),
+                ("B_VAR".to_string(), "2".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_load_passes_variables_to_process() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "TEST_LOAD_CHILD_ENV=child_value").unwrap();
+
+        let args = LoadArgs {
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: expect_value("TEST_LOAD_CHILD_ENV", "child_value"),
+            run: RunArgs {
+                clear_env: true,
+                ..Default::default()
+            },
+        };
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        )
+        .unwrap();
+        assert!(result.unwrap().success());
+        unsafe { env::remove_var("TEST_LOAD_CHILD_ENV") };
+    }
+
+    #[test]
+    fn test_load_expands_references() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "TEST_LOAD_EXPAND_A=1\nTEST_LOAD_EXPAND_B=${{TEST_LOAD_EXPAND_A}}-x"
+        )
+        .unwrap();
+
+        let variables = read_env_input(
+            &env_file(temp_file.path(), false),
+            NameValidation::Permissive,
+            &BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(variables["TEST_LOAD_EXPAND_B"], "1-x");
+    }
+
+    #[test]
+    fn test_load_reference_to_later_key() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            "TEST_LATER_A=$TEST_LATER_B\nTEST_LATER_B=from_file"
+        )
+        .unwrap();
+
+        // Value from environment is used, not the one defined later in file
+        let environment = BTreeMap::from([("TEST_LATER_B".to_string(), "from_env".to_string())]);
+        let variables = read_env_input(
+            &env_file(temp_file.path(), false),
+            NameValidation::Permissive,
+            &environment,
+        )
+        .unwrap();
+        assert_eq!(variables["TEST_LATER_A"], "from_env");
+        assert_eq!(variables["TEST_LATER_B"], "from_file");
+    }
+
+    #[test]
+    fn test_env_inputs_cascade() {
+        let args = LoadArgs {
+            file: vec![".env".to_string()],
+            cascade: Some("production".to_string()),
+            explain: false,
+            env_string: vec![],
+            format: None,
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let paths: Vec<String> = env_inputs(&args)
+            .into_iter()
+            .map(|input| match input.source {
+                EnvSource::File {
+                    path,
+                    optional: true,
+                } => path,
+                source => panic!("Expected optional file, got {:?}", source),
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ".env",
+                ".env.local",
+                ".env.production",
+                ".env.production.local"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_env_inputs() {
+        let mut args = LoadArgs {
+            file: vec![],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        // .env is used by default
+        assert_eq!(env_inputs(&args), vec![env_file(Path::new(".env"), false)]);
+
+        args.file = vec!["env.json".to_string(), "-".to_string()];
+        args.env_string = vec!["A=1".to_string()];
+        assert_eq!(
+            env_inputs(&args),
+            vec![
+                EnvInput {
+                    format: InputFormat::Json,
+                    ..env_file(Path::new("env.json"), false)
+                },
+                EnvInput {
+                    source: EnvSource::Stdin,
+                    format: InputFormat::Dotenv,
+                },
+                EnvInput {
+                    source: EnvSource::Inline("A=1".to_string()),
+                    format: InputFormat::Dotenv,
+                },
+            ]
+        );
+
+        // Inline content replaces default file
+        args.file = vec![];
+        assert_eq!(
+            env_inputs(&args),
+            vec![EnvInput {
+                source: EnvSource::Inline("A=1".to_string()),
+                format: InputFormat::Dotenv,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_inline_input() {
+        let input = EnvInput {
+            source: EnvSource::Inline("A=1\\nB=\"${A}\\n2\"".to_string()),
+            format: InputFormat::Dotenv,
+        };
+        let variables =
+            read_env_input(&input, NameValidation::Permissive, &BTreeMap::new()).unwrap();
+        assert_eq!(variables["A"], "1");
+        assert_eq!(variables["B"], "1\n2");
+    }
+
+    #[test]
+    fn test_read_env_inputs_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join(".env");
+        let local = dir.path().join(".env.local");
+        fs::write(&base, "A=base\nB=base\n").unwrap();
+        fs::write(&local, "B=local-${A}\nC=local\n").unwrap();
+        let file = env_file;
+
+        let loaded = read_env_inputs(
+            &[
+                file(&base, false),
+                file(&dir.path().join(".env.missing"), true),
+                file(&local, false),
+            ],
+            NameValidation::Permissive,
+        )
+        .unwrap();
+        assert_eq!(loaded.values["A"], "base");
+        assert_eq!(loaded.values["B"], "local-base");
+        assert_eq!(loaded.values["C"], "local");
+        assert_eq!(
+            loaded.sources["B"],
+            vec![
+                base.to_string_lossy().to_string(),
+                local.to_string_lossy().to_string()
+            ]
+        );
+
+        // Required files must exist
+        let result = read_env_inputs(
+            &[file(&dir.path().join(".env.missing"), false)],
+            NameValidation::Permissive,
+        );
+        assert!(matches!(result, Err(ErrorKind::FileError(_))));
+        // At least one of optional files must exist
+        let result = read_env_inputs(
+            &[file(&dir.path().join(".env.missing"), true)],
+            NameValidation::Permissive,
+        );
+        assert!(matches!(result, Err(ErrorKind::FileError(_))));
+    }
+
+    #[test]
+    fn test_read_env_input_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let cases = [
+            ("env.json", r#"{"A": "json", "B": 1}"#, "json", "1"),
+            ("env.yaml", "A: yaml\nB: true\n", "yaml", "true"),
+            ("env.toml", "A = \"toml\"\nB = 1.5\n", "toml", "1.5"),
+            (
+                "env.sh",
+                "#!/bin/sh\nexport A='sh' B=\"$A\"-x\n",
+                "sh",
+                "sh-x",
+            ),
+        ];
+        for (name, content, a, b) in cases {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            let input = EnvInput {
+                format: input::detect_format(&path.to_string_lossy()),
+                ..env_file(&path, false)
+            };
+            let variables =
+                read_env_input(&input, NameValidation::Permissive, &BTreeMap::new()).unwrap();
+            assert_eq!(variables["A"], a, "{}", name);
+            assert_eq!(variables["B"], b, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_read_env_input_validates_structured_names() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, r#"{{"INVALID NAME": "value"}}"#).unwrap();
+        let input = EnvInput {
+            format: InputFormat::Json,
+            ..env_file(temp_file.path(), false)
+        };
+        let result = read_env_input(&input, NameValidation::Permissive, &BTreeMap::new());
+        assert!(matches!(result, Err(ErrorKind::NameValidationError(_))));
+    }
+
+    #[test]
+    fn test_load_explain() {
+        let mut first = NamedTempFile::new().unwrap();
+        writeln!(first, "TEST_EXPLAIN_A=1\nTEST_EXPLAIN_B=1").unwrap();
+        let mut second = NamedTempFile::new().unwrap();
+        writeln!(second, "TEST_EXPLAIN_B=2").unwrap();
+        let first_path = first.path().to_string_lossy().to_string();
+        let second_path = second.path().to_string_lossy().to_string();
+
+        let args = LoadArgs {
+            file: vec![first_path.clone(), second_path.clone()],
+            cascade: None,
+            explain: true,
+            env_string: vec![],
+            format: None,
+            global: false,
+            dry_run: false,
+            reveal: false,
+            process: vec![],
+            run: RunArgs::default(),
+        };
+        let mut buffer = vec![];
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            &mut buffer,
+        );
+        assert!(matches!(result, Ok(None)));
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            format!(
+                "TEST_EXPLAIN_A: {first_path}\nTEST_EXPLAIN_B: {second_path} (overrides {first_path})\n"
+            )
+        );
+    }
+
     #[test]
     fn test_load_empty_file() {
         let temp_file = NamedTempFile::new().unwrap();
 
         let args = LoadArgs {
-            file: temp_file.path().to_string_lossy().to_string(),
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = load(&args);
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_ok());
     }
 
@@ -1100,27 +3959,91 @@ TEST_EXPORT_ONE=val"#));
         writeln!(temp_file, "TEST_VAR=test_value\nINVALID NAME=value").unwrap();
 
         let args = LoadArgs {
-            file: temp_file.path().to_string_lossy().to_string(),
+            file: vec![temp_file.path().to_string_lossy().to_string()],
+            cascade: None,
+            explain: false,
+            env_string: vec![],
+            format: None,
             global: false,
+            dry_run: false,
+            reveal: false,
             process: vec![],
+            run: RunArgs::default(),
         };
 
-        let result = load(&args);
+        let result = load(
+            &args,
+            NameValidation::Permissive,
+            &DiffOptions::default(),
+            vec![],
+        );
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), ErrorKind::ParsingError(_)));
     }
 
+    #[test]
+    fn test_lint_files() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "A=1\nA=2 \nB='unterminated\n").unwrap();
+        let path = temp_file.path().to_string_lossy().to_string();
+        let mut args = LintArgs {
+            files: vec![path.clone()],
+            output: ReportFormat::Human,
+            strict: false,
+        };
+
+        let mut buffer = vec![];
+        assert!(!lint_files(&args, NameValidation::Permissive, &mut buffer).unwrap());
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            format!(
+                "{path}:2:1: warning: A is already defined on line 1\n\
+                 {path}:2:4: warning: unquoted value has trailing whitespace, which is removed\n\
+                 {path}:3:3: error: unterminated quoted value\n\
+                 Found 1 errors and 2 warnings\n"
+            )
+        );
+
+        args.output = ReportFormat::Json;
+        let mut buffer = vec![];
+        lint_files(&args, NameValidation::Permissive, &mut buffer).unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(report.as_array().unwrap().len(), 3);
+        assert_eq!(report[2]["file"], path.as_str());
+        assert_eq!(report[2]["line"], 3);
+        assert_eq!(report[2]["column"], 3);
+        assert_eq!(report[2]["severity"], "error");
+        assert_eq!(report[2]["kind"], "syntax-error");
+    }
+
+    #[test]
+    fn test_lint_files_strict() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "A=1\nA=2\n").unwrap();
+        let mut args = LintArgs {
+            files: vec![temp_file.path().to_string_lossy().to_string()],
+            output: ReportFormat::Human,
+            strict: false,
+        };
+        // Warnings fail check only in strict mode
+        assert!(lint_files(&args, NameValidation::Permissive, vec![]).unwrap());
+        args.strict = true;
+        assert!(!lint_files(&args, NameValidation::Permissive, vec![]).unwrap());
+
+        args.files = vec!["nonexistent.env".to_string()];
+        assert!(matches!(
+            lint_files(&args, NameValidation::Permissive, vec![]),
+            Err(ErrorKind::FileError(_))
+        ));
+    }
+
     #[test]
     fn test_run_command_print_env() {
         init();
         unsafe { env::set_var("TEST_PRINT_ENV", "test_value") };
         let mut buffer = vec![];
         assert_eq!(
-            run_command(
-                &Commands::Print(PrintArgs { format: None }),
-                None,
-                &mut buffer
-            ),
+            run_command(&Commands::Print(PrintArgs::default()), None, &mut buffer),
             ExitCode::SUCCESS
         );
         assert!(
@@ -1139,8 +4062,9 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Get(GetArgs {
-                    key: "TEST_SMILAR_VAR".to_string(), // Intentional typo
+                    keys: vec!["TEST_SMILAR_VAR".to_string()], // Intentional typo
                     no_similar_names: false,
+                    ..Default::default()
                 }),
                 None,
                 &mut buffer
@@ -1161,10 +4085,12 @@ TEST_EXPORT_ONE=val"#));
         let mut buffer = vec![];
         let result = run_command(
             &Commands::Set(SetArgs {
-                key: "TEST_SET_RUN".to_string(),
-                value: "test_value".to_string(),
+                variables: vec!["TEST_SET_RUN".to_string(), "test_value".to_string()],
                 global: false,
+                dry_run: false,
+                reveal: false,
                 process: test_cmd,
+                run: RunArgs::default(),
             }),
             None,
             &mut buffer,
@@ -1179,10 +4105,12 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Set(SetArgs {
-                    key: "INVALID NAME".to_string(),
-                    value: "test_value".to_string(),
+                    variables: vec!["INVALID NAME".to_string(), "test_value".to_string()],
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
@@ -1201,15 +4129,19 @@ TEST_EXPORT_ONE=val"#));
                 &Commands::Add(AddArgs {
                     key: "TEST_ADD_EXISTING".to_string(),
                     value: "appended".to_string(),
+                    separator: None,
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
             ),
             ExitCode::SUCCESS
         );
-        assert_eq!(env::var("TEST_ADD_EXISTING").unwrap(), "initial_appended");
+        assert_eq!(env::var("TEST_ADD_EXISTING").unwrap(), "initial_");
         unsafe { env::remove_var("TEST_ADD_EXISTING") };
     }
 
@@ -1222,8 +4154,12 @@ TEST_EXPORT_ONE=val"#));
                 &Commands::Add(AddArgs {
                     key: "INVALID NAME".to_string(),
                     value: "test_value".to_string(),
+                    separator: None,
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
@@ -1239,9 +4175,12 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Delete(DeleteArgs {
-                    key: "NONEXISTENT_VAR".to_string(),
+                    keys: vec!["NONEXISTENT_VAR".to_string()],
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
@@ -1257,9 +4196,16 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Load(LoadArgs {
-                    file: "nonexistent.env".to_string(),
+                    file: vec!["nonexistent.env".to_string()],
+                    cascade: None,
+                    explain: false,
+                    env_string: vec![],
+                    format: None,
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
@@ -1282,16 +4228,22 @@ TEST_EXPORT_ONE=val"#));
         let mut buffer = vec![];
         let result = run_command(
             &Commands::Load(LoadArgs {
-                file: temp_file.path().to_string_lossy().to_string(),
+                file: vec![temp_file.path().to_string_lossy().to_string()],
+                cascade: None,
+                explain: false,
+                env_string: vec![],
+                format: None,
                 global: false,
+                dry_run: false,
+                reveal: false,
                 process: test_cmd,
+                run: RunArgs::default(),
             }),
             None,
             &mut buffer,
         );
         assert_eq!(result, ExitCode::SUCCESS);
-        assert_eq!(env::var("TEST_LOAD_PROC").unwrap(), "test_value");
-        unsafe { env::remove_var("TEST_LOAD_PROC") };
+        assert!(env::var("TEST_LOAD_PROC").is_err());
     }
 
     #[test]
@@ -1300,10 +4252,12 @@ TEST_EXPORT_ONE=val"#));
         let mut buffer = vec![];
         let result = run_command(
             &Commands::Set(SetArgs {
-                key: "TEST_GLOBAL".to_string(),
-                value: "test_value".to_string(),
+                variables: vec!["TEST_GLOBAL".to_string(), "test_value".to_string()],
                 global: true,
+                dry_run: false,
+                reveal: false,
                 process: vec![],
+                run: RunArgs::default(),
             }),
             None,
             &mut buffer,
@@ -1315,9 +4269,12 @@ TEST_EXPORT_ONE=val"#));
                 assert_eq!(
                     run_command(
                         &Commands::Delete(DeleteArgs {
-                            key: "TEST_GLOBAL".to_string(),
+                            keys: vec!["TEST_GLOBAL".to_string()],
                             global: true,
+                            dry_run: false,
+                            reveal: false,
                             process: vec![],
+                            run: RunArgs::default(),
                         }),
                         None,
                         &mut buffer
@@ -1348,17 +4305,20 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Delete(DeleteArgs {
-                    key: "TEST_DELETE_PROC_FAIL".to_string(),
+                    keys: vec!["TEST_DELETE_PROC_FAIL".to_string()],
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: failing_command,
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
             ),
             ExitCode::FAILURE
         );
-        // Variable should still be deleted even if process fails
-        assert!(env::var("TEST_DELETE_PROC_FAIL").is_err());
+        assert_eq!(env::var("TEST_DELETE_PROC_FAIL").unwrap(), "test_value");
+        unsafe { env::remove_var("TEST_DELETE_PROC_FAIL") };
     }
 
     #[test]
@@ -1368,9 +4328,12 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Delete(DeleteArgs {
-                    key: "INVALID NAME".to_string(),
+                    keys: vec!["INVALID NAME".to_string()],
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
@@ -1386,9 +4349,12 @@ TEST_EXPORT_ONE=val"#));
         assert_eq!(
             run_command(
                 &Commands::Delete(DeleteArgs {
-                    key: "".to_string(),
+                    keys: vec!["".to_string()],
                     global: false,
+                    dry_run: false,
+                    reveal: false,
                     process: vec![],
+                    run: RunArgs::default(),
                 }),
                 None,
                 &mut buffer
@@ -1403,6 +4369,7 @@ TEST_EXPORT_ONE=val"#));
         let mut buffer = vec![];
         let config = Config {
             print_format: Some("{name}={value}".to_string()),
+            ..Default::default()
         };
         assert_eq!(
             run_command(&Commands::InitConfig, Some(config), &mut buffer),
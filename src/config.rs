@@ -19,6 +19,11 @@ pub fn get_config_file_path() -> PathBuf {
     get_config_dir().join("envfetch.toml")
 }
 
+/// Get path to directory with snapshots of environment
+pub fn get_snapshots_dir() -> PathBuf {
+    get_config_dir().join("snapshots")
+}
+
 /// Read config file
 pub fn read_config_from_file(path: PathBuf) -> Result<Config, ConfigParsingError> {
     info!("Reading config at {}", path.display());
@@ -58,6 +63,7 @@ fn default_config() -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{NameValidation, SortKey};
     use assert_fs::prelude::*;
     use dirs::config_dir;
 
@@ -65,7 +71,7 @@ mod tests {
 
     impl Write for FailingWriter {
         fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
-            Err(io::Error::new(io::ErrorKind::Other, "Mock write error"))
+            Err(io::Error::other("Mock write error"))
         }
 
         fn flush(&mut self) -> io::Result<()> {
@@ -97,7 +103,7 @@ mod tests {
     #[test]
     fn test_read_config_default() {
         let result = read_config(default_config().to_owned()).unwrap();
-        assert_eq!(result, Config { print_format: None })
+        assert_eq!(result, Config::default())
     }
 
     #[test]
@@ -105,7 +111,7 @@ mod tests {
         let file = assert_fs::NamedTempFile::new("envfetch.toml").unwrap();
         file.write_str(default_config()).unwrap();
         let result = read_config_from_file(file.path().to_path_buf()).unwrap();
-        assert_eq!(result, Config { print_format: None })
+        assert_eq!(result, Config::default())
     }
 
     #[test]
@@ -128,6 +134,32 @@ mod tests {
         assert_eq!(result, Err(ConfigParsingError::FileDoesntExists))
     }
 
+    #[test]
+    fn test_read_config_print_sort() {
+        let result = read_config("print_sort = \"value\"".to_owned()).unwrap();
+        assert_eq!(result.print_sort, Some(SortKey::Value));
+    }
+
+    #[test]
+    fn test_read_config_no_shell() {
+        let result = read_config("no_shell = true".to_owned()).unwrap();
+        assert_eq!(result.no_shell, Some(true));
+    }
+
+    #[test]
+    fn test_read_config_name_validation() {
+        let result = read_config("name_validation = \"strict\"".to_owned()).unwrap();
+        assert_eq!(result.name_validation, Some(NameValidation::Strict));
+        let result = read_config("name_validation = \"anything\"".to_owned());
+        assert!(matches!(result, Err(ConfigParsingError::ParsingError(_))));
+    }
+
+    #[test]
+    fn test_read_config_invalid_print_sort() {
+        let result = read_config("print_sort = \"random\"".to_owned());
+        assert!(matches!(result, Err(ConfigParsingError::ParsingError(_))));
+    }
+
     #[test]
     fn test_read_config_parsing_error() {
         let content = "invalid toml".to_string();
@@ -0,0 +1,222 @@
+use std::env;
+use std::io::{IsTerminal, Write};
+
+use crate::models::{Config, ErrorKind};
+use crate::redact::Redactor;
+
+/// Change of environment variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Variable doesn't exist yet
+    Added { name: String, value: String },
+    /// Variable exists and its value is changed
+    Modified {
+        name: String,
+        old: String,
+        new: String,
+    },
+    /// Existing variable is deleted
+    Removed { name: String, old: String },
+}
+
+impl Change {
+    /// Mask values of secret-looking variable
+    fn mask(self, redactor: &Redactor) -> Self {
+        let mask = |name: &str, value: String| redactor.mask(name, &value).into_owned();
+        match self {
+            Change::Added { name, value } => Change::Added {
+                value: mask(&name, value),
+                name,
+            },
+            Change::Modified { name, old, new } => Change::Modified {
+                old: mask(&name, old),
+                new: mask(&name, new),
+                name,
+            },
+            Change::Removed { name, old } => Change::Removed {
+                old: mask(&name, old),
+                name,
+            },
+        }
+    }
+}
+
+/// Compute changes for updates of variables, where `None` means deleting variable.
+///
+/// Current values are looked up using `current`, updates that don't change anything are skipped
+pub fn changes(
+    updates: &[(String, Option<String>)],
+    current: impl Fn(&str) -> Option<String>,
+) -> Vec<Change> {
+    updates
+        .iter()
+        .filter_map(|(name, value)| {
+            let name = name.clone();
+            match (current(&name), value.clone()) {
+                (None, Some(value)) => Some(Change::Added { name, value }),
+                (Some(old), Some(new)) if old != new => Some(Change::Modified { name, old, new }),
+                (Some(old), None) => Some(Change::Removed { name, old }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Options for writing changes
+#[derive(Debug, Default)]
+pub struct DiffOptions {
+    /// Redactor, used to mask values of secret-looking variables
+    pub redactor: Redactor,
+    /// Whether changes should be colored
+    pub color: bool,
+}
+
+impl DiffOptions {
+    /// Create options using secret patterns from config, changes are colored if output is terminal
+    pub fn new(config: Option<&Config>, reveal: bool) -> Result<Self, ErrorKind> {
+        Ok(Self {
+            redactor: Redactor::new(config, reveal)?,
+            color: color_enabled(),
+        })
+    }
+
+    /// Write changes to buffer, masking secrets
+    pub fn write<W: Write>(&self, changes: Vec<Change>, buffer: W) {
+        let changes: Vec<Change> = changes
+            .into_iter()
+            .map(|change| change.mask(&self.redactor))
+            .collect();
+        write_changes(&changes, self.color, buffer);
+    }
+}
+
+/// Check if diff should be colored, i.e. output is terminal and NO_COLOR isn't set
+pub fn color_enabled() -> bool {
+    std::io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// Write changes to buffer, one per line
+pub fn write_changes<W: Write>(changes: &[Change], color: bool, mut buffer: W) {
+    let paint = |code: &str, text: String| {
+        if color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text
+        }
+    };
+    if changes.is_empty() {
+        writeln!(buffer, "No changes").expect("Failed to write to buffer");
+        return;
+    }
+    for change in changes {
+        let line = match change {
+            Change::Added { name, value } => paint("32", format!("+ {} = {:?}", name, value)),
+            Change::Modified { name, old, new } => {
+                paint("33", format!("~ {} = {:?} → {:?}", name, old, new))
+            }
+            Change::Removed { name, old } => paint("31", format!("- {} = {:?}", name, old)),
+        };
+        writeln!(buffer, "{}", line).expect("Failed to write to buffer");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(name: &str, value: Option<&str>) -> (String, Option<String>) {
+        (name.to_owned(), value.map(str::to_owned))
+    }
+
+    fn current(name: &str) -> Option<String> {
+        match name {
+            "EXISTING" => Some("old".to_owned()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let updates = vec![
+            update("NEW", Some("value")),
+            update("EXISTING", Some("new")),
+            update("EXISTING", None),
+            update("EXISTING", Some("old")),
+            update("MISSING", None),
+        ];
+        assert_eq!(
+            changes(&updates, current),
+            vec![
+                Change::Added {
+                    name: "NEW".to_owned(),
+                    value: "value".to_owned()
+                },
+                Change::Modified {
+                    name: "EXISTING".to_owned(),
+                    old: "old".to_owned(),
+                    new: "new".to_owned()
+                },
+                Change::Removed {
+                    name: "EXISTING".to_owned(),
+                    old: "old".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_changes() {
+        let changes = changes(
+            &[
+                update("NEW", Some("value")),
+                update("EXISTING", Some("new")),
+            ],
+            current,
+        );
+        let mut buffer = vec![];
+        write_changes(&changes, false, &mut buffer);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "+ NEW = \"value\"\n~ EXISTING = \"old\" → \"new\"\n"
+        );
+
+        let mut buffer = vec![];
+        write_changes(&changes[..1], true, &mut buffer);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "\x1b[32m+ NEW = \"value\"\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_write_no_changes() {
+        let mut buffer = vec![];
+        write_changes(&[], true, &mut buffer);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "No changes\n");
+    }
+
+    #[test]
+    fn test_write_masked() {
+        let changes = changes(
+            &[
+                update("API_TOKEN", Some("secret")),
+                update("EXISTING", None),
+            ],
+            current,
+        );
+        let mut buffer = vec![];
+        DiffOptions::default().write(changes.clone(), &mut buffer);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "+ API_TOKEN = \"********\"\n- EXISTING = \"old\"\n"
+        );
+
+        let options = DiffOptions {
+            redactor: Redactor::disabled(),
+            color: false,
+        };
+        let mut buffer = vec![];
+        options.write(changes, &mut buffer);
+        assert!(String::from_utf8(buffer).unwrap().contains("\"secret\""));
+    }
+}
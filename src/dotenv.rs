@@ -0,0 +1,674 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::models::ErrorKind;
+
+/// Quotes around value in dotenv file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quote {
+    /// Value without quotes, variables are expanded
+    None,
+    /// Value in single quotes, taken literally
+    Single,
+    /// Value in double quotes, escapes and variables are expanded
+    Double,
+}
+
+/// Variable definition from dotenv file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Variable's name
+    pub key: String,
+    /// Value as written in file, without quotes
+    pub raw: String,
+    /// Quotes around value
+    pub quote: Quote,
+    /// Line where variable is defined, starting from 1, or 0 if it's unknown
+    pub line: usize,
+    /// Column where variable's name starts, starting from 1, or 0 if it's unknown
+    pub column: usize,
+}
+
+/// Error at given position in file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl From<PositionError> for ErrorKind {
+    fn from(error: PositionError) -> Self {
+        position_error(error.line, error.column, error.message)
+    }
+}
+
+/// Create error for given position in file
+pub fn position_error(line: usize, column: usize, message: impl Display) -> ErrorKind {
+    ErrorKind::ParsingError(format!("line {}, column {}: {}", line, column, message))
+}
+
+/// Create error for given position in line of dotenv file
+fn line_error(line: usize, column: usize, message: &str) -> PositionError {
+    PositionError {
+        line,
+        column,
+        message: message.to_owned(),
+    }
+}
+
+/// Parse content of dotenv file.
+///
+/// Supports comments, `export` prefix, unquoted, single-quoted and double-quoted values.
+/// Values are returned as is, use [`resolve`] to expand escapes and variables
+pub fn parse(content: &str) -> Result<Vec<Entry>, ErrorKind> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut entries = vec![];
+    for (index, line) in content.lines().enumerate() {
+        if let Some(entry) = parse_line(line, index + 1)? {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Get position where variable's name starts, after indentation and optional export keyword
+pub fn name_start(chars: &[char]) -> usize {
+    let position = skip_whitespace(chars, 0);
+    let rest: String = chars[position..].iter().collect();
+    if let Some(after) = rest.strip_prefix("export")
+        && after.starts_with([' ', '\t'])
+    {
+        return skip_whitespace(chars, position + "export".len());
+    }
+    position
+}
+
+/// Parse single line of dotenv file, returns `None` for empty lines and comments
+pub fn parse_line(line: &str, number: usize) -> Result<Option<Entry>, PositionError> {
+    let chars: Vec<char> = line.chars().collect();
+    let position = skip_whitespace(&chars, 0);
+    if position == chars.len() || chars[position] == '#' {
+        return Ok(None);
+    }
+
+    let key_start = name_start(&chars);
+    let mut position = key_start;
+    while position < chars.len()
+        && (chars[position].is_ascii_alphanumeric() || chars[position] == '_')
+    {
+        position += 1;
+    }
+    let key: String = chars[key_start..position].iter().collect();
+    if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(line_error(number, key_start + 1, "invalid variable name"));
+    }
+
+    position = skip_whitespace(&chars, position);
+    if chars.get(position) != Some(&'=') {
+        return Err(line_error(number, position + 1, "expected '='"));
+    }
+    position = skip_whitespace(&chars, position + 1);
+
+    let (raw, quote) = match chars.get(position) {
+        Some(&quote @ ('"' | '\'')) => {
+            let start = position + 1;
+            let mut end = start;
+            while end < chars.len() && chars[end] != quote {
+                // Quote can be escaped only in double quotes
+                if quote == '"' && chars[end] == '\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            if end >= chars.len() {
+                return Err(line_error(
+                    number,
+                    position + 1,
+                    "unterminated quoted value",
+                ));
+            }
+            let raw: String = chars[start..end].iter().collect();
+            position = skip_whitespace(&chars, end + 1);
+            if position < chars.len() && chars[position] != '#' {
+                return Err(line_error(
+                    number,
+                    position + 1,
+                    "unexpected characters after quoted value",
+                ));
+            }
+            let quote = if quote == '"' {
+                Quote::Double
+            } else {
+                Quote::Single
+            };
+            (raw, quote)
+        }
+        _ => {
+            // Comment in unquoted value must be separated by whitespace
+            let mut end = position;
+            while end < chars.len()
+                && !(chars[end] == '#' && end > position && chars[end - 1].is_whitespace())
+            {
+                end += 1;
+            }
+            let raw: String = chars[position..end].iter().collect();
+            (raw.trim_end().to_owned(), Quote::None)
+        }
+    };
+
+    Ok(Some(Entry {
+        key,
+        raw,
+        quote,
+        line: number,
+        column: key_start + 1,
+    }))
+}
+
+/// Get position of first non-whitespace character starting from given one
+pub fn skip_whitespace(chars: &[char], mut position: usize) -> usize {
+    while position < chars.len() && matches!(chars[position], ' ' | '\t') {
+        position += 1;
+    }
+    position
+}
+
+/// Format definition of variable for dotenv file, so it's read back as is.
+///
+/// Fails if name can't be read from dotenv file
+pub fn format_entry(name: &str, value: &str) -> Result<String, ErrorKind> {
+    let is_valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_valid_name {
+        return Err(ErrorKind::SerializationError(format!(
+            "Variable name '{}' can't be written to dotenv file",
+            name
+        )));
+    }
+    Ok(format!("{}={}", name, quote_value(value)))
+}
+
+/// Write variables as content of dotenv file, one per line
+pub fn write(variables: &[(String, String)]) -> Result<String, ErrorKind> {
+    let mut content = String::new();
+    for (name, value) in variables {
+        content.push_str(&format_entry(name, value)?);
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// Quote value for dotenv file, leaving it as is if it is safe.
+///
+/// Single quotes are preferred, as their content is taken literally,
+/// double quotes with escapes are used for quotes and line breaks
+pub fn quote_value(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-@%+=:,./".contains(c);
+    if value.chars().all(is_safe) {
+        return value.to_owned();
+    }
+    if !value.contains(['\'', '\n', '\r']) {
+        return format!("'{}'", value);
+    }
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '"' | '\\' | '$' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// State of resolving value of entry
+#[derive(Debug, Clone)]
+enum State {
+    Pending,
+    InProgress,
+    Done(String),
+}
+
+/// Resolves values of entries, expanding references to other variables
+struct Resolver<'a, F: Fn(&str) -> Option<String>> {
+    entries: &'a [Entry],
+    env: F,
+    states: Vec<State>,
+    /// Entries being resolved, used to report cycles
+    stack: Vec<usize>,
+}
+
+impl<F: Fn(&str) -> Option<String>> Resolver<'_, F> {
+    /// Get value of entry with given index
+    fn resolve(&mut self, index: usize) -> Result<String, ErrorKind> {
+        match &self.states[index] {
+            State::Done(value) => return Ok(value.clone()),
+            State::InProgress => {
+                let start = self
+                    .stack
+                    .iter()
+                    .position(|i| *i == index)
+                    .unwrap_or_default();
+                let mut names: Vec<&str> = self.stack[start..]
+                    .iter()
+                    .map(|i| self.entries[*i].key.as_str())
+                    .collect();
+                names.push(&self.entries[index].key);
+                return Err(ErrorKind::CyclicReference(names.join(" -> ")));
+            }
+            State::Pending => {}
+        }
+        self.states[index] = State::InProgress;
+        self.stack.push(index);
+        let entry = &self.entries[index];
+        let value = match entry.quote {
+            Quote::Single => entry.raw.clone(),
+            Quote::None | Quote::Double => {
+                let chars: Vec<char> = entry.raw.chars().collect();
+                self.expand(&chars, entry.quote == Quote::Double, index)?
+            }
+        };
+        self.stack.pop();
+        self.states[index] = State::Done(value.clone());
+        Ok(value)
+    }
+
+    /// Get value of variable, referenced from entry with given index.
+    ///
+    /// Latest definition before entry is used, like in shell, then existing environment
+    fn lookup(&mut self, name: &str, from: usize) -> Result<Option<String>, ErrorKind> {
+        let defined = self.entries[..from]
+            .iter()
+            .rposition(|entry| entry.key == name);
+        match defined {
+            Some(index) => self.resolve(index).map(Some),
+            None => Ok((self.env)(name)),
+        }
+    }
+
+    /// Expand escapes (if enabled) and references to variables in text
+    fn expand(&mut self, chars: &[char], escapes: bool, from: usize) -> Result<String, ErrorKind> {
+        let line = self.entries[from].line;
+        let key = self.entries[from].key.clone();
+        let mut result = String::new();
+        let mut position = 0;
+        while position < chars.len() {
+            match chars[position] {
+                '\\' if escapes && position + 1 < chars.len() => {
+                    match chars[position + 1] {
+                        'n' => result.push('\n'),
+                        'r' => result.push('\r'),
+                        't' => result.push('\t'),
+                        c @ ('"' | '\\' | '$') => result.push(c),
+                        // Unknown escapes are kept as is
+                        c => {
+                            result.push('\\');
+                            result.push(c);
+                        }
+                    }
+                    position += 2;
+                }
+                '$' if chars.get(position + 1) == Some(&'{') => {
+                    let start = position + 2;
+                    let end = find_closing_brace(chars, start).ok_or_else(|| {
+                        ErrorKind::ParsingError(format!(
+                            "line {}: unterminated '${{' in value of {}",
+                            line, key
+                        ))
+                    })?;
+                    result.push_str(&self.expand_braced(&chars[start..end], escapes, from)?);
+                    position = end + 1;
+                }
+                '$' if chars
+                    .get(position + 1)
+                    .is_some_and(|c| c.is_ascii_alphabetic() || *c == '_') =>
+                {
+                    let start = position + 1;
+                    let mut end = start;
+                    while end < chars.len()
+                        && (chars[end].is_ascii_alphanumeric() || chars[end] == '_')
+                    {
+                        end += 1;
+                    }
+                    let name: String = chars[start..end].iter().collect();
+                    result.push_str(&self.lookup(&name, from)?.unwrap_or_default());
+                    position = end;
+                }
+                c => {
+                    result.push(c);
+                    position += 1;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Expand content of `${...}`, e.g. `VAR`, `VAR:-default` or `VAR:?error`
+    fn expand_braced(
+        &mut self,
+        chars: &[char],
+        escapes: bool,
+        from: usize,
+    ) -> Result<String, ErrorKind> {
+        let length = chars
+            .iter()
+            .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+            .count();
+        let name: String = chars[..length].iter().collect();
+        let operator: String = chars[length..].iter().take(2).collect();
+        let is_valid = !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && (length == chars.len() || operator == ":-" || operator == ":?");
+        if !is_valid {
+            return Err(ErrorKind::ParsingError(format!(
+                "line {}: invalid reference '${{{}}}' in value of {}",
+                self.entries[from].line,
+                chars.iter().collect::<String>(),
+                self.entries[from].key
+            )));
+        }
+        let word = &chars[(length + 2).min(chars.len())..];
+        let operator = (length < chars.len()).then_some(operator.as_str());
+        let value = self.lookup(&name, from)?.filter(|value| !value.is_empty());
+        match (value, operator) {
+            (Some(value), _) => Ok(value),
+            (None, Some(":-")) => self.expand(word, escapes, from),
+            (None, Some(_)) => {
+                let message = self.expand(word, escapes, from)?;
+                let message = if message.is_empty() {
+                    "parameter is empty or not set".to_owned()
+                } else {
+                    message
+                };
+                Err(ErrorKind::ParsingError(format!(
+                    "line {}: {}: {}",
+                    self.entries[from].line, name, message
+                )))
+            }
+            (None, None) => Ok(String::new()),
+        }
+    }
+}
+
+/// Find position of brace, closing `${`, taking nested ones into account
+fn find_closing_brace(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (position, c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(position),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Resolve values of entries, expanding escapes and references to variables.
+///
+/// References are looked up in entries first and then in existing environment
+/// using `env`. If variable is defined several times, last definition wins
+pub fn resolve(
+    entries: &[Entry],
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<BTreeMap<String, String>, ErrorKind> {
+    let mut resolver = Resolver {
+        entries,
+        env,
+        states: vec![State::Pending; entries.len()],
+        stack: vec![],
+    };
+    let mut variables = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let value = resolver.resolve(index)?;
+        variables.insert(entry.key.clone(), value);
+    }
+    Ok(variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_str(content: &str) -> Result<BTreeMap<String, String>, ErrorKind> {
+        resolve(&parse(content).unwrap(), |name| match name {
+            "EXISTING" => Some("from_env".to_owned()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        })
+    }
+
+    fn value(content: &str, key: &str) -> String {
+        resolve_str(content).unwrap()[key].clone()
+    }
+
+    #[test]
+    fn test_parse() {
+        let entries = parse(
+            "# comment\n\nexport A=1\nB = 'two words' # comment\nC=\"x\\\"y\"\nD=value # comment\nE=\n",
+        )
+        .unwrap();
+        let parsed: Vec<(&str, &str, Quote, usize)> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.key.as_str(),
+                    entry.raw.as_str(),
+                    entry.quote,
+                    entry.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("A", "1", Quote::None, 3),
+                ("B", "two words", Quote::Single, 4),
+                ("C", "x\\\"y", Quote::Double, 5),
+                ("D", "value", Quote::None, 6),
+                ("E", "", Quote::None, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_hash_inside_value() {
+        assert_eq!(
+            value("URL=http://host/#anchor", "URL"),
+            "http://host/#anchor"
+        );
+        assert_eq!(value("A='#not comment'", "A"), "#not comment");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = vec![
+            ("A B=1", "line 1, column 3: expected '='"),
+            ("1A=1", "line 1, column 1: invalid variable name"),
+            ("A=1\n=1", "line 2, column 1: invalid variable name"),
+            ("A=\"value", "line 1, column 3: unterminated quoted value"),
+            ("A='value' rest", "line 1, column 11: unexpected characters"),
+        ];
+        for (content, expected) in cases {
+            match parse(content) {
+                Err(ErrorKind::ParsingError(err)) => {
+                    assert!(err.starts_with(expected), "{}: {}", content, err)
+                }
+                result => panic!("Expected ParsingError for {}, got {:?}", content, result),
+            }
+        }
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(value("A=\"a\\nb\\t\\\\\\$\"", "A"), "a\nb\t\\$");
+        // Escapes aren't processed outside of double quotes
+        assert_eq!(value("A='a\\nb'", "A"), "a\\nb");
+        assert_eq!(value("A=a\\nb", "A"), "a\\nb");
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let content =
+            "DB_USER=admin\nDB_HOST=localhost\nDATABASE_URL=postgres://${DB_USER}@${DB_HOST}";
+        assert_eq!(value(content, "DATABASE_URL"), "postgres://admin@localhost");
+        assert_eq!(value("A=$EXISTING/bin", "A"), "from_env/bin");
+        assert_eq!(value("A=\"${EXISTING}\"", "A"), "from_env");
+        assert_eq!(value("A=${MISSING}", "A"), "");
+        // Variables aren't expanded in single quotes
+        assert_eq!(value("A='${EXISTING}'", "A"), "${EXISTING}");
+        assert_eq!(value("A=\"\\${EXISTING}\"", "A"), "${EXISTING}");
+        assert_eq!(value("A=price: 5$", "A"), "price: 5$");
+    }
+
+    #[test]
+    fn test_interpolation_order() {
+        // Earlier definition is used, self-reference is taken from environment
+        assert_eq!(value("EXISTING=${EXISTING}:x", "EXISTING"), "from_env:x");
+        assert_eq!(value("A=1\nB=${A}\nA=2", "B"), "1");
+        // Later definitions aren't used, like in shell
+        assert_eq!(value("B=${A}\nA=2", "B"), "");
+        assert_eq!(value("B=${EXISTING}\nEXISTING=2", "B"), "from_env");
+    }
+
+    #[test]
+    fn test_interpolation_default() {
+        assert_eq!(value("A=${MISSING:-default}", "A"), "default");
+        assert_eq!(value("A=${EMPTY:-default}", "A"), "default");
+        assert_eq!(value("A=${EXISTING:-default}", "A"), "from_env");
+        assert_eq!(value("A=${MISSING:-${EXISTING}}", "A"), "from_env");
+        assert_eq!(value("B=b\nA=${MISSING:-$B-x}", "A"), "b-x");
+    }
+
+    #[test]
+    fn test_interpolation_error() {
+        match resolve_str("A=${MISSING:?must be set}") {
+            Err(ErrorKind::ParsingError(err)) => assert_eq!(err, "line 1: MISSING: must be set"),
+            result => panic!("Expected ParsingError, got {:?}", result),
+        }
+        assert!(matches!(
+            resolve_str("A=${MISSING:?}"),
+            Err(ErrorKind::ParsingError(_))
+        ));
+        assert!(resolve_str("A=${EXISTING:?must be set}").is_ok());
+        assert!(matches!(
+            resolve_str("A=${UNTERMINATED"),
+            Err(ErrorKind::ParsingError(_))
+        ));
+        assert!(matches!(
+            resolve_str("A=${IN VALID}"),
+            Err(ErrorKind::ParsingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_interpolation_cycle() {
+        // References to later keys are taken from environment, so they don't form cycle
+        let variables = resolve_str("A=${B}\nB=${C}\nC=$A-c").unwrap();
+        assert_eq!(variables["A"], "");
+        assert_eq!(variables["C"], "-c");
+        assert_eq!(
+            value("A=${EXISTING}\nEXISTING=${A}x", "EXISTING"),
+            "from_envx"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        assert_eq!(value("A=1\nA=2", "A"), "2");
+        // Redefinitions extending variable aren't a cycle
+        let content = "PATH_T=$PATH_T:/a\nPATH_T=$PATH_T:/b\nPATH_T=/base";
+        assert_eq!(value(content, "PATH_T"), "/base");
+        let content = "EXISTING=$EXISTING:/a\nEXISTING=$EXISTING:/b\nB=$EXISTING";
+        assert_eq!(value(content, "B"), "from_env:/a:/b");
+    }
+
+    /// Write variables and read them back without environment
+    fn round_trip(variables: &[(String, String)]) -> BTreeMap<String, String> {
+        let content = write(variables).unwrap();
+        resolve(&parse(&content).unwrap(), |_| None).unwrap()
+    }
+
+    #[test]
+    fn test_quote_value() {
+        assert_eq!(quote_value("/usr/bin:/bin"), "/usr/bin:/bin");
+        assert_eq!(quote_value(""), "");
+        assert_eq!(quote_value(" a # b "), "' a # b '");
+        assert_eq!(quote_value("$HOME \\n"), "'$HOME \\n'");
+        assert_eq!(quote_value("it's $HOME"), "\"it's \\$HOME\"");
+        assert_eq!(quote_value("a\tb\n\"c\"\\"), "\"a\\tb\\n\\\"c\\\"\\\\\"");
+    }
+
+    #[test]
+    fn test_format_entry_invalid_name() {
+        assert_eq!(format_entry("_A1", "x").unwrap(), "_A1=x");
+        for name in ["", "1A", "A-B", "A.B", "A B", "ПУТЬ"] {
+            assert!(matches!(
+                format_entry(name, "x"),
+                Err(ErrorKind::SerializationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let values = [
+            "",
+            "plain",
+            " leading and trailing ",
+            "# not a comment",
+            "value # not a comment",
+            "'single'",
+            "\"double\"",
+            "it's \"mixed\"",
+            "line1\nline2",
+            "crlf\r\n",
+            "\ttab",
+            "back\\slash\\n",
+            "$HOME ${HOME} ${MISSING:-default}",
+            "it's $HOME",
+            "\\$",
+            "=",
+            "юникод ✓",
+            "\u{1b}[0m",
+        ];
+        let variables: Vec<(String, String)> = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (format!("VAR_{}", index), value.to_string()))
+            .collect();
+        let parsed = round_trip(&variables);
+        for (name, value) in &variables {
+            assert_eq!(&parsed[name], value, "value of {} changed", name);
+        }
+    }
+
+    #[test]
+    fn test_write_round_trip_generated() {
+        // Deterministic pseudo-random values built from characters with special meaning
+        let alphabet: Vec<char> = "aZ0 _-=#'\"\\$`{}:?!\n\r\t é".chars().collect();
+        let mut seed: u64 = 42;
+        let mut variables = vec![];
+        for index in 0..500 {
+            let mut value = String::new();
+            for _ in 0..index % 12 {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                value.push(alphabet[(seed >> 33) as usize % alphabet.len()]);
+            }
+            variables.push((format!("VAR_{}", index), value));
+        }
+        let parsed = round_trip(&variables);
+        for (name, value) in &variables {
+            assert_eq!(&parsed[name], value, "value of {} changed", name);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use glob::Pattern;
+use regex::Regex;
+
+use crate::models::{ErrorKind, ExportArgs, FilterArgs};
+
+/// Filter for list of variables, built from command line args
+#[derive(Debug, Default)]
+pub struct Filter {
+    globs: Vec<Pattern>,
+    regexes: Vec<Regex>,
+    prefixes: Vec<String>,
+    value_contains: Vec<String>,
+    invert: bool,
+}
+
+impl Filter {
+    /// Create filter, validating all patterns
+    pub fn new(args: &FilterArgs) -> Result<Self, ErrorKind> {
+        Ok(Self {
+            globs: compile_globs(&args.globs)?,
+            regexes: compile_regexes(&args.regexes)?,
+            prefixes: args.prefixes.clone(),
+            value_contains: args.value_contains.clone(),
+            invert: args.invert,
+        })
+    }
+
+    /// Check if variable passes filter.
+    ///
+    /// Variable must match at least one pattern of each given kind,
+    /// result is inverted if `invert` is set.
+    pub fn matches(&self, name: &str, value: &str) -> bool {
+        let matches = (self.globs.is_empty() || self.globs.iter().any(|glob| glob.matches(name)))
+            && (self.regexes.is_empty() || self.regexes.iter().any(|regex| regex.is_match(name)))
+            && (self.prefixes.is_empty()
+                || self.prefixes.iter().any(|prefix| name.starts_with(prefix)))
+            && (self.value_contains.is_empty()
+                || self.value_contains.iter().any(|part| value.contains(part)));
+        matches != self.invert
+    }
+
+    /// Keep only variables that pass filter
+    pub fn apply(&self, variables: Vec<(String, String)>) -> Vec<(String, String)> {
+        variables
+            .into_iter()
+            .filter(|(name, value)| self.matches(name, value))
+            .collect()
+    }
+}
+
+/// Selection of variables for export by patterns of their names
+#[derive(Debug, Default)]
+pub struct Selection {
+    all: bool,
+    globs: Vec<Pattern>,
+    regexes: Vec<Regex>,
+    excludes: Vec<Pattern>,
+}
+
+impl Selection {
+    /// Create selection, validating all patterns
+    pub fn new(args: &ExportArgs) -> Result<Self, ErrorKind> {
+        Ok(Self {
+            all: args.all,
+            globs: compile_globs(&args.globs)?,
+            regexes: compile_regexes(&args.regexes)?,
+            excludes: compile_globs(&args.excludes)?,
+        })
+    }
+
+    /// Get names matched by each pattern, names are sorted and excluded ones are skipped.
+    ///
+    /// With `all` set, single entry with all names is returned
+    pub fn matches(&self, names: &[&str]) -> Vec<(String, Vec<String>)> {
+        let select = |matches: &dyn Fn(&str) -> bool| {
+            let mut selected: Vec<String> = names
+                .iter()
+                .filter(|name| matches(name) && !self.is_excluded(name))
+                .map(|name| name.to_string())
+                .collect();
+            selected.sort();
+            selected
+        };
+        if self.all {
+            return vec![("--all".to_owned(), select(&|_| true))];
+        }
+        let globs = self
+            .globs
+            .iter()
+            .map(|glob| (glob.as_str().to_owned(), select(&|name| glob.matches(name))));
+        let regexes = self.regexes.iter().map(|regex| {
+            (
+                regex.as_str().to_owned(),
+                select(&|name| regex.is_match(name)),
+            )
+        });
+        globs.chain(regexes).collect()
+    }
+
+    /// Check if variable with given name is excluded
+    pub fn is_excluded(&self, name: &str) -> bool {
+        self.excludes.iter().any(|exclude| exclude.matches(name))
+    }
+}
+
+/// Compile glob patterns, reporting invalid one
+fn compile_globs(globs: &[String]) -> Result<Vec<Pattern>, ErrorKind> {
+    globs
+        .iter()
+        .map(|glob| {
+            Pattern::new(glob).map_err(|err| ErrorKind::PatternError(format!("{glob}: {err}")))
+        })
+        .collect()
+}
+
+/// Compile regular expressions, reporting invalid one
+fn compile_regexes(regexes: &[String]) -> Result<Vec<Regex>, ErrorKind> {
+    regexes
+        .iter()
+        .map(|regex| {
+            Regex::new(regex).map_err(|err| ErrorKind::PatternError(format!("{regex}: {err}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> Vec<(String, String)> {
+        vec![
+            ("AWS_REGION".to_string(), "eu-west-1".to_string()),
+            ("AWS_PROFILE".to_string(), "dev".to_string()),
+            ("CARGO_HOME".to_string(), "/home/user/.cargo".to_string()),
+            ("HOME".to_string(), "/home/user".to_string()),
+        ]
+    }
+
+    fn names(variables: Vec<(String, String)>) -> Vec<String> {
+        variables.into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = Filter::new(&FilterArgs::default()).unwrap();
+        assert_eq!(filter.apply(variables()), variables());
+    }
+
+    #[test]
+    fn test_filter_glob() {
+        let filter = Filter::new(&FilterArgs {
+            globs: vec!["AWS_*".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            names(filter.apply(variables())),
+            vec!["AWS_REGION", "AWS_PROFILE"]
+        );
+    }
+
+    #[test]
+    fn test_filter_multiple_globs() {
+        let filter = Filter::new(&FilterArgs {
+            globs: vec!["AWS_R*".to_string(), "?OME".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(names(filter.apply(variables())), vec!["AWS_REGION", "HOME"]);
+    }
+
+    #[test]
+    fn test_filter_regex() {
+        let filter = Filter::new(&FilterArgs {
+            regexes: vec!["HOME$".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(names(filter.apply(variables())), vec!["CARGO_HOME", "HOME"]);
+    }
+
+    #[test]
+    fn test_filter_prefix() {
+        let filter = Filter::new(&FilterArgs {
+            prefixes: vec!["CARGO_".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(names(filter.apply(variables())), vec!["CARGO_HOME"]);
+    }
+
+    #[test]
+    fn test_filter_value_contains() {
+        let filter = Filter::new(&FilterArgs {
+            value_contains: vec!["/home/user".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(names(filter.apply(variables())), vec!["CARGO_HOME", "HOME"]);
+    }
+
+    #[test]
+    fn test_filter_combined() {
+        let filter = Filter::new(&FilterArgs {
+            globs: vec!["*HOME".to_string()],
+            value_contains: vec!["cargo".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(names(filter.apply(variables())), vec!["CARGO_HOME"]);
+    }
+
+    #[test]
+    fn test_filter_invert() {
+        let filter = Filter::new(&FilterArgs {
+            prefixes: vec!["AWS_".to_string()],
+            invert: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(names(filter.apply(variables())), vec!["CARGO_HOME", "HOME"]);
+    }
+
+    #[test]
+    fn test_filter_invalid_glob() {
+        let result = Filter::new(&FilterArgs {
+            globs: vec!["[AWS".to_string()],
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ErrorKind::PatternError(_))));
+    }
+
+    #[test]
+    fn test_filter_invalid_regex() {
+        let result = Filter::new(&FilterArgs {
+            regexes: vec!["(AWS".to_string()],
+            ..Default::default()
+        });
+        match result {
+            Err(ErrorKind::PatternError(err)) => assert!(err.starts_with("(AWS: ")),
+            _ => panic!("Expected PatternError"),
+        }
+    }
+
+    fn selection(args: ExportArgs) -> Vec<(String, Vec<String>)> {
+        let variables = variables();
+        let names: Vec<&str> = variables.iter().map(|(name, _)| name.as_str()).collect();
+        Selection::new(&args).unwrap().matches(&names)
+    }
+
+    #[test]
+    fn test_selection_patterns() {
+        assert_eq!(
+            selection(ExportArgs {
+                globs: vec!["AWS_*".to_string(), "NONE_*".to_string()],
+                regexes: vec!["HOME$".to_string()],
+                ..Default::default()
+            }),
+            vec![
+                (
+                    "AWS_*".to_string(),
+                    vec!["AWS_PROFILE".to_string(), "AWS_REGION".to_string()]
+                ),
+                ("NONE_*".to_string(), vec![]),
+                (
+                    "HOME$".to_string(),
+                    vec!["CARGO_HOME".to_string(), "HOME".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_selection_all_with_excludes() {
+        assert_eq!(
+            selection(ExportArgs {
+                all: true,
+                excludes: vec!["AWS_*".to_string()],
+                ..Default::default()
+            }),
+            vec![(
+                "--all".to_string(),
+                vec!["CARGO_HOME".to_string(), "HOME".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_selection_invalid_exclude() {
+        let result = Selection::new(&ExportArgs {
+            excludes: vec!["[AWS".to_string()],
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(ErrorKind::PatternError(_))));
+    }
+}
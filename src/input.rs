@@ -0,0 +1,626 @@
+use std::path::Path;
+
+use crate::dotenv::{self, Entry, Quote};
+use crate::models::{ErrorKind, InputFormat};
+
+/// Detect format of file with variables by its extension, dotenv is used by default
+pub fn detect_format(path: &str) -> InputFormat {
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("json") => InputFormat::Json,
+        Some("yaml" | "yml") => InputFormat::Yaml,
+        Some("toml") => InputFormat::Toml,
+        Some("sh" | "bash" | "zsh") => InputFormat::Shell,
+        _ => InputFormat::Dotenv,
+    }
+}
+
+/// Parse content of file with variables in given format.
+///
+/// Values from structured formats are taken literally, while values from dotenv
+/// and shell files can reference other variables
+pub fn parse(content: &str, format: InputFormat) -> Result<Vec<Entry>, ErrorKind> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    match format {
+        InputFormat::Dotenv => dotenv::parse(content),
+        InputFormat::Shell => parse_shell(content),
+        InputFormat::Json => parse_json(content),
+        InputFormat::Yaml => parse_yaml(content),
+        InputFormat::Toml => parse_toml(content),
+    }
+}
+
+/// Replace `\n` sequences outside of quoted values with line breaks.
+///
+/// Allows to write several variables in one line of command line
+pub fn unescape_newlines(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut quote = None;
+    // Quotes are special only at the beginning of value
+    let mut value_start = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', None) if chars.peek() == Some(&'n') => {
+                chars.next();
+                result.push('\n');
+                value_start = false;
+                continue;
+            }
+            // Escaped character in double quotes can't close them
+            ('\\', Some('"')) => {
+                result.push(c);
+                result.extend(chars.next());
+                continue;
+            }
+            ('\'' | '"', None) if value_start => quote = Some(c),
+            (_, Some(current)) if c == current || c == '\n' => quote = None,
+            _ => {}
+        }
+        if quote.is_none() && !matches!(c, ' ' | '\t') {
+            value_start = c == '=';
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Create entry with literal value
+fn literal(key: String, value: String) -> Entry {
+    Entry {
+        key,
+        raw: value,
+        quote: Quote::Single,
+        line: 0,
+        column: 0,
+    }
+}
+
+/// Create error for value that can't be used as value of variable
+fn invalid_value(key: &str, kind: &str) -> ErrorKind {
+    ErrorKind::ParsingError(format!(
+        "{}: expected string, number or boolean, got {}",
+        key, kind
+    ))
+}
+
+/// Create error for top-level value that isn't list of variables
+fn invalid_root(kind: &str) -> ErrorKind {
+    ErrorKind::ParsingError(format!(
+        "expected object with variables or list of name and value pairs, got {}",
+        kind
+    ))
+}
+
+/// Remove position, which is appended to message by serde_json and serde_yaml
+fn strip_position(message: String, line: usize, column: usize) -> String {
+    let suffix = format!(" at line {} column {}", line, column);
+    match message.strip_suffix(&suffix) {
+        Some(message) => message.to_owned(),
+        None => message,
+    }
+}
+
+/// Get line and column of byte offset in content, both starting from 1
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Parse JSON object or array of objects with `name` and `value` fields
+fn parse_json(content: &str) -> Result<Vec<Entry>, ErrorKind> {
+    use serde_json::Value;
+
+    let root: Value = serde_json::from_str(content).map_err(|err| {
+        let message = strip_position(err.to_string(), err.line(), err.column());
+        dotenv::position_error(err.line(), err.column(), message)
+    })?;
+    let scalar = |key: &str, value: &Value| match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Null => Err(invalid_value(key, "null")),
+        Value::Array(_) => Err(invalid_value(key, "array")),
+        Value::Object(_) => Err(invalid_value(key, "object")),
+    };
+    match &root {
+        Value::Object(object) => object
+            .iter()
+            .map(|(key, value)| Ok(literal(key.clone(), scalar(key, value)?)))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let name = match item.get("name") {
+                    Some(Value::String(name)) => name.clone(),
+                    _ => {
+                        return Err(ErrorKind::ParsingError(format!(
+                            "item {}: expected string field 'name'",
+                            index + 1
+                        )));
+                    }
+                };
+                let value = match item.get("value") {
+                    Some(value) => scalar(&name, value)?,
+                    None => String::new(),
+                };
+                Ok(literal(name, value))
+            })
+            .collect(),
+        Value::Null => Err(invalid_root("null")),
+        _ => Err(invalid_root("scalar")),
+    }
+}
+
+/// Parse YAML mapping or sequence of mappings with `name` and `value` fields
+fn parse_yaml(content: &str) -> Result<Vec<Entry>, ErrorKind> {
+    use serde_yaml::Value;
+
+    let root: Value = serde_yaml::from_str(content).map_err(|err| match err.location() {
+        Some(location) => dotenv::position_error(
+            location.line(),
+            location.column(),
+            strip_position(err.to_string(), location.line(), location.column()),
+        ),
+        None => ErrorKind::ParsingError(err.to_string()),
+    })?;
+    let scalar = |key: &str, value: &Value| match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Null => Err(invalid_value(key, "null")),
+        Value::Sequence(_) => Err(invalid_value(key, "sequence")),
+        Value::Mapping(_) => Err(invalid_value(key, "mapping")),
+        Value::Tagged(_) => Err(invalid_value(key, "tagged value")),
+    };
+    let key = |key: &Value| match key {
+        Value::String(key) => Ok(key.clone()),
+        _ => Err(ErrorKind::ParsingError(format!(
+            "names of variables must be strings, got {:?}",
+            key
+        ))),
+    };
+    match &root {
+        Value::Mapping(mapping) => mapping
+            .iter()
+            .map(|(name, value)| {
+                let name = key(name)?;
+                let value = scalar(&name, value)?;
+                Ok(literal(name, value))
+            })
+            .collect(),
+        Value::Sequence(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let name = match item.get("name") {
+                    Some(name) => key(name)?,
+                    None => {
+                        return Err(ErrorKind::ParsingError(format!(
+                            "item {}: expected field 'name'",
+                            index + 1
+                        )));
+                    }
+                };
+                let value = match item.get("value") {
+                    Some(value) => scalar(&name, value)?,
+                    None => String::new(),
+                };
+                Ok(literal(name, value))
+            })
+            .collect(),
+        // Empty document
+        Value::Null => Ok(vec![]),
+        _ => Err(invalid_root("scalar")),
+    }
+}
+
+/// Parse TOML table, nested tables aren't allowed
+fn parse_toml(content: &str) -> Result<Vec<Entry>, ErrorKind> {
+    use toml::Value;
+
+    let table: toml::Table = toml::from_str(content).map_err(|err| match err.span() {
+        Some(span) => {
+            let (line, column) = line_column(content, span.start);
+            dotenv::position_error(line, column, err.message().trim_end())
+        }
+        None => ErrorKind::ParsingError(err.message().to_owned()),
+    })?;
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(value) => value,
+                Value::Integer(value) => value.to_string(),
+                Value::Float(value) => value.to_string(),
+                Value::Boolean(value) => value.to_string(),
+                Value::Datetime(value) => value.to_string(),
+                Value::Array(_) => return Err(invalid_value(&key, "array")),
+                Value::Table(_) => return Err(invalid_value(&key, "table")),
+            };
+            Ok(literal(key, value))
+        })
+        .collect()
+}
+
+/// Parse shell script with `export KEY=value` and `KEY=value` lines.
+///
+/// Words are unquoted like in POSIX shell and converted to double-quoted dotenv
+/// values, so references to variables are expanded in the same way
+fn parse_shell(content: &str) -> Result<Vec<Entry>, ErrorKind> {
+    let mut entries = vec![];
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        let chars: Vec<char> = line.chars().collect();
+        let mut position = dotenv::skip_whitespace(&chars, 0);
+        if position == chars.len() || chars[position] == '#' {
+            continue;
+        }
+
+        let word_end = chars[position..]
+            .iter()
+            .position(|c| c.is_whitespace())
+            .map_or(chars.len(), |end| position + end);
+        let export = chars[position..word_end].iter().collect::<String>() == "export";
+        if export {
+            position = dotenv::skip_whitespace(&chars, word_end);
+        }
+
+        // Export can define several variables at once
+        loop {
+            let key_start = position;
+            while position < chars.len()
+                && (chars[position].is_ascii_alphanumeric() || chars[position] == '_')
+            {
+                position += 1;
+            }
+            let key: String = chars[key_start..position].iter().collect();
+            if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(dotenv::position_error(
+                    number,
+                    key_start + 1,
+                    "expected variable assignment",
+                ));
+            }
+            match chars.get(position) {
+                Some('=') => {
+                    let raw = read_word(&chars, &mut position, number)?;
+                    entries.push(Entry {
+                        key,
+                        raw,
+                        quote: Quote::Double,
+                        line: number,
+                        column: key_start + 1,
+                    });
+                }
+                // Exporting existing variable doesn't change its value
+                Some(' ' | '\t') | None if export => {}
+                _ => {
+                    return Err(dotenv::position_error(number, position + 1, "expected '='"));
+                }
+            }
+            position = dotenv::skip_whitespace(&chars, position);
+            if position == chars.len() || chars[position] == '#' {
+                break;
+            }
+            if !export {
+                return Err(dotenv::position_error(
+                    number,
+                    position + 1,
+                    "unexpected characters after value",
+                ));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Read shell word after `=` at `position` and convert it to double-quoted dotenv value
+fn read_word(chars: &[char], position: &mut usize, line: usize) -> Result<String, ErrorKind> {
+    let mut raw = String::new();
+    // Skip '='
+    *position += 1;
+    while let Some(&c) = chars.get(*position) {
+        match c {
+            ' ' | '\t' => break,
+            '\\' => {
+                let Some(&next) = chars.get(*position + 1) else {
+                    return Err(dotenv::position_error(
+                        line,
+                        *position + 1,
+                        "line continuation is not supported",
+                    ));
+                };
+                push_literal(&mut raw, next);
+                *position += 2;
+            }
+            '\'' => {
+                let start = *position;
+                *position += 1;
+                while chars.get(*position).is_some_and(|&c| c != '\'') {
+                    push_literal(&mut raw, chars[*position]);
+                    *position += 1;
+                }
+                if *position == chars.len() {
+                    return Err(dotenv::position_error(
+                        line,
+                        start + 1,
+                        "unterminated quoted value",
+                    ));
+                }
+                *position += 1;
+            }
+            '"' => {
+                let start = *position;
+                *position += 1;
+                loop {
+                    match chars.get(*position) {
+                        None => {
+                            return Err(dotenv::position_error(
+                                line,
+                                start + 1,
+                                "unterminated quoted value",
+                            ));
+                        }
+                        Some('"') => break,
+                        Some('\\') => {
+                            // Only few characters can be escaped in double quotes
+                            match chars.get(*position + 1) {
+                                Some(&next @ ('$' | '"' | '\\')) => {
+                                    raw.push('\\');
+                                    raw.push(next);
+                                }
+                                Some('`') => raw.push('`'),
+                                Some(&next) => {
+                                    raw.push_str("\\\\");
+                                    raw.push(next);
+                                }
+                                None => {
+                                    return Err(dotenv::position_error(
+                                        line,
+                                        start + 1,
+                                        "unterminated quoted value",
+                                    ));
+                                }
+                            }
+                            *position += 1;
+                        }
+                        Some('`') => return Err(command_substitution(line, *position)),
+                        Some('$') if chars.get(*position + 1) == Some(&'(') => {
+                            return Err(command_substitution(line, *position));
+                        }
+                        Some(&c) => raw.push(c),
+                    }
+                    *position += 1;
+                }
+                *position += 1;
+            }
+            '`' => return Err(command_substitution(line, *position)),
+            '$' if chars.get(*position + 1) == Some(&'(') => {
+                return Err(command_substitution(line, *position));
+            }
+            // References to variables are expanded outside of single quotes
+            '$' => {
+                raw.push('$');
+                *position += 1;
+            }
+            ';' | '|' | '&' | '<' | '>' | '(' | ')' => {
+                return Err(dotenv::position_error(
+                    line,
+                    *position + 1,
+                    format!("unexpected character '{}'", c),
+                ));
+            }
+            c => {
+                push_literal(&mut raw, c);
+                *position += 1;
+            }
+        }
+    }
+    Ok(raw)
+}
+
+/// Push character to double-quoted dotenv value, so it's taken literally
+fn push_literal(raw: &mut String, c: char) {
+    if matches!(c, '"' | '\\' | '$') {
+        raw.push('\\');
+    }
+    raw.push(c);
+}
+
+/// Create error for command substitution, which can't be evaluated
+fn command_substitution(line: usize, position: usize) -> ErrorKind {
+    dotenv::position_error(line, position + 1, "command substitution is not supported")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn variables(content: &str, format: InputFormat) -> BTreeMap<String, String> {
+        let entries = parse(content, format).unwrap();
+        dotenv::resolve(&entries, |name| match name {
+            "EXISTING" => Some("from_env".to_owned()),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    fn error(content: &str, format: InputFormat) -> String {
+        match parse(content, format) {
+            Err(ErrorKind::ParsingError(message)) => message,
+            result => panic!("Expected parsing error, got {:?}", result),
+        }
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format("env.json"), InputFormat::Json);
+        assert_eq!(detect_format("config/env.YAML"), InputFormat::Yaml);
+        assert_eq!(detect_format("env.yml"), InputFormat::Yaml);
+        assert_eq!(detect_format("env.toml"), InputFormat::Toml);
+        assert_eq!(detect_format("env.sh"), InputFormat::Shell);
+        assert_eq!(detect_format(".env"), InputFormat::Dotenv);
+        assert_eq!(detect_format(".env.local"), InputFormat::Dotenv);
+    }
+
+    #[test]
+    fn test_unescape_newlines() {
+        assert_eq!(unescape_newlines("A=1\\nB=2"), "A=1\nB=2");
+        assert_eq!(
+            unescape_newlines("A='x\\ny'\\nB=\"x\\\"\\ny\"\\nC=3"),
+            "A='x\\ny'\nB=\"x\\\"\\ny\"\nC=3"
+        );
+        assert_eq!(unescape_newlines("A=1\nB='2\\n"), "A=1\nB='2\\n");
+        assert_eq!(unescape_newlines("A=it's\\nB=2"), "A=it's\nB=2");
+    }
+
+    #[test]
+    fn test_parse_json() {
+        assert_eq!(
+            variables(
+                r#"{"NAME": "$EXISTING", "PORT": 8080, "DEBUG": false}"#,
+                InputFormat::Json
+            ),
+            pairs(&[("NAME", "$EXISTING"), ("PORT", "8080"), ("DEBUG", "false")])
+        );
+        assert_eq!(
+            variables(
+                r#"[{"name": "A", "value": "1"}, {"name": "B"}]"#,
+                InputFormat::Json
+            ),
+            pairs(&[("A", "1"), ("B", "")])
+        );
+    }
+
+    #[test]
+    fn test_parse_json_errors() {
+        assert_eq!(
+            error("{\n  \"A\": \"1\",\n}", InputFormat::Json),
+            "line 3, column 1: trailing comma"
+        );
+        assert_eq!(
+            error(r#"{"A": {"B": "1"}}"#, InputFormat::Json),
+            "A: expected string, number or boolean, got object"
+        );
+        assert_eq!(
+            error(r#"[{"value": "1"}]"#, InputFormat::Json),
+            "item 1: expected string field 'name'"
+        );
+        assert_eq!(
+            error("1", InputFormat::Json),
+            "expected object with variables or list of name and value pairs, got scalar"
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        assert_eq!(
+            variables("A: one\nB: 2\nC: 'true'\n", InputFormat::Yaml),
+            pairs(&[("A", "one"), ("B", "2"), ("C", "true")])
+        );
+        // Kubernetes-style list of variables
+        assert_eq!(
+            variables(
+                "- name: A\n  value: one\n- name: B\n  value: two\n",
+                InputFormat::Yaml
+            ),
+            pairs(&[("A", "one"), ("B", "two")])
+        );
+        assert!(variables("", InputFormat::Yaml).is_empty());
+    }
+
+    #[test]
+    fn test_parse_yaml_errors() {
+        assert!(error("A: one\nB: [two\n", InputFormat::Yaml).starts_with("line 3, column 1: "));
+        assert_eq!(
+            error("A:\n  B: one\n", InputFormat::Yaml),
+            "A: expected string, number or boolean, got mapping"
+        );
+        assert_eq!(
+            error("A: ~\n", InputFormat::Yaml),
+            "A: expected string, number or boolean, got null"
+        );
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        assert_eq!(
+            variables("A = \"one\"\nB = 2\nC = true\n", InputFormat::Toml),
+            pairs(&[("A", "one"), ("B", "2"), ("C", "true")])
+        );
+    }
+
+    #[test]
+    fn test_parse_toml_errors() {
+        assert!(error("A = \"one\"\nB = \n", InputFormat::Toml).starts_with("line 2, column "));
+        assert_eq!(
+            error("[section]\nA = 1\n", InputFormat::Toml),
+            "section: expected string, number or boolean, got table"
+        );
+    }
+
+    #[test]
+    fn test_parse_shell() {
+        assert_eq!(
+            variables(
+                "#!/bin/sh\n# comment\nexport A=1 B='single $A' C=\"double $A\"\nD=it\\'s'' # comment\nexport E=$EXISTING\\$ F=\"\\n\\$\"\nexport HOME\n",
+                InputFormat::Shell
+            ),
+            pairs(&[
+                ("A", "1"),
+                ("B", "single $A"),
+                ("C", "double 1"),
+                ("D", "it's"),
+                ("E", "from_env$"),
+                ("F", "\\n$"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_shell_errors() {
+        let cases = vec![
+            ("echo hello", "line 1, column 5: expected '='"),
+            (
+                "A=1 B=2",
+                "line 1, column 5: unexpected characters after value",
+            ),
+            (
+                "export A='1",
+                "line 1, column 10: unterminated quoted value",
+            ),
+            ("A=\"1", "line 1, column 3: unterminated quoted value"),
+            (
+                "A=$(pwd)",
+                "line 1, column 3: command substitution is not supported",
+            ),
+            (
+                "A=`pwd`",
+                "line 1, column 3: command substitution is not supported",
+            ),
+            ("A=1; B=2", "line 1, column 4: unexpected character ';'"),
+            (
+                "A=1\\",
+                "line 1, column 4: line continuation is not supported",
+            ),
+            ("1A=1", "line 1, column 1: expected variable assignment"),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(error(content, InputFormat::Shell), expected, "{}", content);
+        }
+    }
+}
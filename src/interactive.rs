@@ -4,6 +4,7 @@ pub mod state;
 pub mod tests;
 pub mod view;
 
+use crate::redact::Redactor;
 use crate::variables; // Function to get environment variables.
 use ratatui::{Terminal, backend::Backend};
 use std::io;
@@ -13,10 +14,10 @@ pub struct InteractiveApp {
 }
 
 impl InteractiveApp {
-    pub fn new() -> Self {
-        Self {
-            state: state::AppState::new(variables::get_variables()),
-        }
+    pub fn new(redactor: Redactor) -> Self {
+        let mut state = state::AppState::new(variables::get_variables());
+        state.redactor = redactor;
+        Self { state }
     }
 
     pub fn run<B>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()>
@@ -7,16 +7,15 @@ use std::time::Duration;
 // This function is used in interactive.rs, so disable this useless warning
 #[allow(dead_code)]
 pub fn handle_input(state: &mut AppState) -> io::Result<()> {
-    if event::poll(Duration::from_millis(100))? {
-        if let Event::Key(key_event) = event::read()? {
-            if key_event.kind == KeyEventKind::Press {
-                match state.mode.clone() {
-                    Mode::List => handle_list_mode(state, key_event),
-                    Mode::Add => handle_add_mode(state, key_event),
-                    Mode::Edit(_) => handle_edit_mode(state, key_event),
-                    Mode::Delete(_) => handle_delete_mode(state, key_event),
-                }
-            }
+    if event::poll(Duration::from_millis(100))?
+        && let Event::Key(key_event) = event::read()?
+        && key_event.kind == KeyEventKind::Press
+    {
+        match state.mode.clone() {
+            Mode::List => handle_list_mode(state, key_event),
+            Mode::Add => handle_add_mode(state, key_event),
+            Mode::Edit(_) => handle_edit_mode(state, key_event),
+            Mode::Delete(_) => handle_delete_mode(state, key_event),
         }
     }
 
@@ -24,10 +23,10 @@ pub fn handle_input(state: &mut AppState) -> io::Result<()> {
         state.reload();
     }
 
-    if let Some(expiry) = state.message_expiry {
-        if std::time::Instant::now() > expiry {
-            state.clear_message();
-        }
+    if let Some(expiry) = state.message_expiry
+        && std::time::Instant::now() > expiry
+    {
+        state.clear_message();
     }
     Ok(())
 }
@@ -57,21 +56,26 @@ pub fn handle_list_mode(state: &mut AppState, key: KeyEvent) {
                 state.mode = Mode::Delete(k.clone());
             }
         }
-        KeyCode::Down => {
-            if state.current_index < state.entries.len().saturating_sub(1) {
-                state.current_index += 1;
-                let visible = 10;
-                if state.current_index >= state.scroll_offset + visible {
-                    state.scroll_offset += 1;
-                }
+        KeyCode::Char('s') => {
+            state.show_secrets = !state.show_secrets;
+            let message = if state.show_secrets {
+                "Secrets shown"
+            } else {
+                "Secrets hidden"
+            };
+            state.show_message(message, Duration::from_secs(2));
+        }
+        KeyCode::Down if state.current_index < state.entries.len().saturating_sub(1) => {
+            state.current_index += 1;
+            let visible = 10;
+            if state.current_index >= state.scroll_offset + visible {
+                state.scroll_offset += 1;
             }
         }
-        KeyCode::Up => {
-            if state.current_index > 0 {
-                state.current_index -= 1;
-                if state.current_index < state.scroll_offset {
-                    state.scroll_offset = state.current_index;
-                }
+        KeyCode::Up if state.current_index > 0 => {
+            state.current_index -= 1;
+            if state.current_index < state.scroll_offset {
+                state.scroll_offset = state.current_index;
             }
         }
         KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -186,21 +190,15 @@ pub fn handle_edit_mode(state: &mut AppState, key: KeyEvent) {
             }
         }
         KeyCode::Esc => state.mode = Mode::List,
-        KeyCode::Left => {
-            if state.input_cursor_value > 0 {
-                state.input_cursor_value -= 1;
-            }
+        KeyCode::Left if state.input_cursor_value > 0 => {
+            state.input_cursor_value -= 1;
         }
-        KeyCode::Right => {
-            if state.input_cursor_value < state.input_value.len() {
-                state.input_cursor_value += 1;
-            }
+        KeyCode::Right if state.input_cursor_value < state.input_value.len() => {
+            state.input_cursor_value += 1;
         }
-        KeyCode::Backspace => {
-            if state.input_cursor_value > 0 {
-                state.input_value.remove(state.input_cursor_value - 1);
-                state.input_cursor_value -= 1;
-            }
+        KeyCode::Backspace if state.input_cursor_value > 0 => {
+            state.input_value.remove(state.input_cursor_value - 1);
+            state.input_cursor_value -= 1;
         }
         KeyCode::Char(c) => {
             state.input_value.insert(state.input_cursor_value, c);
@@ -240,23 +238,23 @@ mod tests {
 
     // This test-only function lets us inject a simulated event, covering branches in handle_input.
     pub fn handle_input_with_event(state: &mut AppState, evt: Option<Event>) -> io::Result<()> {
-        if let Some(Event::Key(key_event)) = evt {
-            if key_event.kind == KeyEventKind::Press {
-                match state.mode.clone() {
-                    Mode::List => handle_list_mode(state, key_event),
-                    Mode::Add => handle_add_mode(state, key_event),
-                    Mode::Edit(_) => handle_edit_mode(state, key_event),
-                    Mode::Delete(_) => handle_delete_mode(state, key_event),
-                }
+        if let Some(Event::Key(key_event)) = evt
+            && key_event.kind == KeyEventKind::Press
+        {
+            match state.mode.clone() {
+                Mode::List => handle_list_mode(state, key_event),
+                Mode::Add => handle_add_mode(state, key_event),
+                Mode::Edit(_) => handle_edit_mode(state, key_event),
+                Mode::Delete(_) => handle_delete_mode(state, key_event),
             }
         }
         if state.reload_requested {
             state.reload();
         }
-        if let Some(expiry) = state.message_expiry {
-            if std::time::Instant::now() > expiry {
-                state.clear_message();
-            }
+        if let Some(expiry) = state.message_expiry
+            && std::time::Instant::now() > expiry
+        {
+            state.clear_message();
         }
         Ok(())
     }
@@ -376,6 +374,18 @@ mod tests {
         assert_eq!(state.mode, Mode::Delete("VAR1".to_string()));
     }
 
+    #[test]
+    fn test_handle_list_mode_toggle_secrets() {
+        let mut state = AppState::new(vec![("API_TOKEN".to_string(), "secret".to_string())]);
+        let key_event = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::empty());
+        handle_list_mode(&mut state, key_event);
+        assert!(state.show_secrets);
+        assert_eq!(state.display_value("API_TOKEN", "secret"), "secret");
+        handle_list_mode(&mut state, key_event);
+        assert!(!state.show_secrets);
+        assert_eq!(state.display_value("API_TOKEN", "secret"), "********");
+    }
+
     #[test]
     fn test_handle_list_mode_down() {
         let mut state = AppState::new(vec![
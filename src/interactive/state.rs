@@ -1,5 +1,7 @@
 use std::time::{Duration, Instant};
 
+use crate::redact::Redactor;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Mode {
     List,
@@ -35,6 +37,10 @@ pub struct AppState {
     pub input_focus: InputFocus,
     // Flag to indicate a reload request.
     pub reload_requested: bool,
+    // Masks values of secret-looking variables.
+    pub redactor: Redactor,
+    // Whether secret values are shown instead of masked.
+    pub show_secrets: bool,
     // Optional variable getter override for testing.
     #[cfg(test)]
     pub variable_getter: VariableGetter,
@@ -56,6 +62,8 @@ impl AppState {
             input_cursor_value: 0,
             input_focus: InputFocus::Key,
             reload_requested: false,
+            redactor: Redactor::default(),
+            show_secrets: false,
             #[cfg(test)]
             variable_getter: None,
         }
@@ -73,6 +81,15 @@ impl AppState {
         self.message_expiry = None;
     }
 
+    /// Get value of variable to display, masking it if it looks like secret.
+    pub fn display_value<'a>(&self, key: &str, value: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.show_secrets {
+            value.into()
+        } else {
+            self.redactor.mask(key, value)
+        }
+    }
+
     /// Request a reload of the variable list.
     pub fn request_reload(&mut self) {
         self.reload_requested = true;
@@ -4,6 +4,7 @@ use crate::interactive::{
     InteractiveApp,
     state::{AppState, InputFocus, Mode},
 };
+use crate::redact::Redactor;
 use std::time::Duration;
 
 #[test]
@@ -51,11 +52,11 @@ fn test_edit_variable() {
     let mut state = AppState::new(vec![("VAR1".to_string(), "OLD".to_string())]);
     state.mode = Mode::Edit("VAR1".to_string());
     state.input_value = "NEW".to_string();
-    if let Mode::Edit(ref key) = state.mode {
-        if let Some(entry) = state.entries.iter_mut().find(|(k, _)| k == key) {
-            entry.1 = state.input_value.trim().to_string();
-            state.mode = Mode::List;
-        }
+    if let Mode::Edit(ref key) = state.mode
+        && let Some(entry) = state.entries.iter_mut().find(|(k, _)| k == key)
+    {
+        entry.1 = state.input_value.trim().to_string();
+        state.mode = Mode::List;
     }
     assert_eq!(state.entries[0], ("VAR1".to_string(), "NEW".to_string()));
 }
@@ -92,14 +93,14 @@ fn test_reload() {
 
 #[test]
 fn test_interactive_app_creation() {
-    let app = InteractiveApp::new();
+    let app = InteractiveApp::new(Redactor::default());
     assert!(!app.state.should_quit);
     assert!(!app.state.entries.is_empty());
 }
 
 #[test]
 fn test_app_quit_state() {
-    let mut app = InteractiveApp::new();
+    let mut app = InteractiveApp::new(Redactor::default());
     app.state.should_quit = true;
 
     let backend = TestBackend::new(20, 20);
@@ -22,7 +22,7 @@ pub fn render(state: &AppState, f: &mut Frame) {
                 .map(|(i, (k, v))| {
                     let marker = if i == state.current_index { "> " } else { "  " };
                     let key_field = format!("{:30}", k);
-                    let content = format!("{}{}  {}", marker, key_field, v);
+                    let content = format!("{}{}  {}", marker, key_field, state.display_value(k, v));
                     let style = if i == state.current_index {
                         Style::default()
                             .fg(Color::Green)
@@ -138,7 +138,7 @@ pub fn render(state: &AppState, f: &mut Frame) {
     let footer_text = if let Some(ref msg) = state.message {
         msg.clone()
     } else {
-        "Press [a]dd, [e]dit, [d]elete, [Ctrl+r] reload, [Ctrl+q] quit".to_string()
+        "Press [a]dd, [e]dit, [d]elete, [s]how secrets, [Ctrl+r] reload, [Ctrl+q] quit".to_string()
     };
     let footer = Paragraph::new(footer_text)
         .block(
@@ -198,6 +198,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_draw_list_mode_masks_secrets() -> io::Result<()> {
+        let backend = TestBackend::new(80, 10);
+        let mut state = AppState::new(vec![
+            ("API_TOKEN".to_string(), "hunter2".to_string()),
+            ("HOME".to_string(), "/home/user".to_string()),
+        ]);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| super::render(&state, f))?;
+        let content = buffer_content(&terminal);
+        assert!(content.contains("********"));
+        assert!(!content.contains("hunter2"));
+        assert!(content.contains("/home/user"));
+
+        state.show_secrets = true;
+        terminal.draw(|f| super::render(&state, f))?;
+        assert!(buffer_content(&terminal).contains("hunter2"));
+        Ok(())
+    }
+
+    fn buffer_content(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
     #[test]
     fn test_draw_add_mode() -> io::Result<()> {
         let backend = TestBackend::new(80, 30);
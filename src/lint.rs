@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::dotenv::{self, Entry, Quote};
+use crate::models::NameValidation;
+use crate::utils::validate_var_name;
+
+/// Severity of problem
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// File can be loaded, but probably not as intended
+    Warning,
+    /// File can't be loaded
+    Error,
+}
+
+/// Kinds of problems in dotenv file
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProblemKind {
+    /// Line can't be parsed, e.g. quotes aren't closed
+    SyntaxError,
+    /// Variable's name isn't valid
+    InvalidName,
+    /// Variable is defined several times
+    DuplicateKey,
+    /// Unquoted value ends with whitespace, which is removed
+    TrailingWhitespace,
+    /// Unknown escape sequence in double-quoted value
+    InvalidEscape,
+    /// File starts with byte order mark
+    ByteOrderMark,
+    /// File uses Windows line endings
+    CrlfLineEnding,
+}
+
+impl ProblemKind {
+    /// Get severity of problems of this kind
+    pub fn severity(self) -> Severity {
+        match self {
+            ProblemKind::SyntaxError | ProblemKind::InvalidName => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// Problem at given position in file
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    pub kind: ProblemKind,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(line: usize, column: usize, kind: ProblemKind, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            severity: kind.severity(),
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+/// Check content of dotenv file, problems are returned in order of their positions
+pub fn lint(content: &str, names: NameValidation) -> Vec<Problem> {
+    let mut problems = vec![];
+    let content = match content.strip_prefix('\u{feff}') {
+        Some(content) => {
+            problems.push(Problem::new(
+                1,
+                1,
+                ProblemKind::ByteOrderMark,
+                "file starts with byte order mark",
+            ));
+            content
+        }
+        None => content,
+    };
+
+    let mut crlf_reported = false;
+    let mut definitions: HashMap<String, usize> = HashMap::new();
+    for (index, line) in content.split('\n').enumerate() {
+        let number = index + 1;
+        let line = match line.strip_suffix('\r') {
+            Some(line) => {
+                // Report only first line, as the whole file usually has same line endings
+                if !crlf_reported {
+                    problems.push(Problem::new(
+                        number,
+                        line.chars().count() + 1,
+                        ProblemKind::CrlfLineEnding,
+                        "line ends with CRLF, use LF line endings",
+                    ));
+                    crlf_reported = true;
+                }
+                line
+            }
+            None => line,
+        };
+        let entry = match dotenv::parse_line(line, number) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => continue,
+            Err(error) => {
+                problems.push(invalid_name(line, number, names).unwrap_or_else(|| {
+                    Problem::new(
+                        error.line,
+                        error.column,
+                        ProblemKind::SyntaxError,
+                        error.message,
+                    )
+                }));
+                continue;
+            }
+        };
+
+        match definitions.get(&entry.key) {
+            Some(first) => problems.push(Problem::new(
+                number,
+                entry.column,
+                ProblemKind::DuplicateKey,
+                format!("{} is already defined on line {}", entry.key, first),
+            )),
+            None => {
+                definitions.insert(entry.key.clone(), number);
+            }
+        }
+        problems.extend(lint_value(line, &entry));
+    }
+    problems
+}
+
+/// Check name in line, which can't be parsed, to report invalid name instead of syntax error
+fn invalid_name(line: &str, number: usize, names: NameValidation) -> Option<Problem> {
+    let chars: Vec<char> = line.chars().collect();
+    let start = dotenv::name_start(&chars);
+    let end = start + chars[start..].iter().position(|c| *c == '=')?;
+    let name: String = chars[start..end].iter().collect();
+    let name = name.trim_end();
+    // Dotenv files allow only portable names, even if other ones are allowed by rules
+    let message = validate_var_name(name, names)
+        .and_then(|_| validate_var_name(name, NameValidation::Strict))
+        .err()?;
+    Some(Problem::new(
+        number,
+        start + 1,
+        ProblemKind::InvalidName,
+        message,
+    ))
+}
+
+/// Check value of entry defined in given line
+fn lint_value(line: &str, entry: &Entry) -> Vec<Problem> {
+    let chars: Vec<char> = line.chars().collect();
+    // Find where value starts, after name and '='
+    let position = dotenv::skip_whitespace(&chars, entry.column - 1 + entry.key.chars().count());
+    let start = dotenv::skip_whitespace(&chars, position + 1);
+
+    let mut problems = vec![];
+    match entry.quote {
+        Quote::None => {
+            // Whitespace before comment is required, so only end of line is checked
+            let value = &chars[start..];
+            let has_comment = value
+                .iter()
+                .enumerate()
+                .any(|(index, c)| *c == '#' && index > 0 && value[index - 1].is_whitespace());
+            let trimmed = value
+                .iter()
+                .rposition(|c| !c.is_whitespace())
+                .map_or(0, |end| end + 1);
+            if !has_comment && trimmed < value.len() {
+                problems.push(Problem::new(
+                    entry.line,
+                    start + trimmed + 1,
+                    ProblemKind::TrailingWhitespace,
+                    "unquoted value has trailing whitespace, which is removed",
+                ));
+            }
+        }
+        Quote::Double => {
+            let raw: Vec<char> = entry.raw.chars().collect();
+            let mut index = 0;
+            while index < raw.len() {
+                if raw[index] == '\\' {
+                    if let Some(&next) = raw.get(index + 1)
+                        && !matches!(next, 'n' | 'r' | 't' | '"' | '\\' | '$')
+                    {
+                        problems.push(Problem::new(
+                            entry.line,
+                            // Skip opening quote
+                            start + index + 2,
+                            ProblemKind::InvalidEscape,
+                            format!("unknown escape sequence '\\{}' is kept as is", next),
+                        ));
+                    }
+                    index += 1;
+                }
+                index += 1;
+            }
+        }
+        Quote::Single => {}
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(content: &str) -> Vec<(usize, usize, ProblemKind)> {
+        lint(content, NameValidation::Permissive)
+            .into_iter()
+            .map(|problem| (problem.line, problem.column, problem.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_valid_file() {
+        let content =
+            "# comment\nexport A=1\nB = 'single \\q'\nC=\"line\\nnext\"\nD=value # comment\nE=\n";
+        assert!(lint(content, NameValidation::Permissive).is_empty());
+    }
+
+    #[test]
+    fn test_lint_duplicate_key() {
+        let problems = lint("A=1\nB=2\n  A=3\n", NameValidation::Permissive);
+        assert_eq!(
+            problems,
+            vec![Problem {
+                line: 3,
+                column: 3,
+                severity: Severity::Warning,
+                kind: ProblemKind::DuplicateKey,
+                message: "A is already defined on line 1".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lint_syntax_errors() {
+        assert_eq!(
+            problems("A='unterminated\nB\nC=\"x\" y\nD=ok\n"),
+            vec![
+                (1, 3, ProblemKind::SyntaxError),
+                (2, 2, ProblemKind::SyntaxError),
+                (3, 7, ProblemKind::SyntaxError),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_invalid_name() {
+        assert_eq!(
+            problems("my-var=1\nexport my.var=2\n1A=3\nB C=4\n=5\nD=ok\n"),
+            vec![
+                (1, 1, ProblemKind::InvalidName),
+                (2, 8, ProblemKind::InvalidName),
+                (3, 1, ProblemKind::InvalidName),
+                (4, 1, ProblemKind::InvalidName),
+                (5, 1, ProblemKind::InvalidName),
+            ]
+        );
+        let problems = lint("my-var=1\n", NameValidation::Permissive);
+        assert_eq!(problems[0].severity, Severity::Error);
+        assert!(
+            problems[0]
+                .message
+                .starts_with("Invalid character '-' at position 3 in variable name 'my-var'")
+        );
+    }
+
+    #[test]
+    fn test_lint_trailing_whitespace() {
+        assert_eq!(
+            problems("A=value  \nB=value # comment\nC= x \nD='value'  \n"),
+            vec![
+                (1, 8, ProblemKind::TrailingWhitespace),
+                (3, 5, ProblemKind::TrailingWhitespace),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_invalid_escape() {
+        assert_eq!(
+            problems("A=\"\\\\q \\q \\$\"\nB = \"x\\d\"\n"),
+            vec![
+                (1, 8, ProblemKind::InvalidEscape),
+                (2, 7, ProblemKind::InvalidEscape),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_bom_and_crlf() {
+        assert_eq!(
+            problems("\u{feff}A=1\r\nB=2\r\n"),
+            vec![
+                (1, 1, ProblemKind::ByteOrderMark),
+                (1, 4, ProblemKind::CrlfLineEnding),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(ProblemKind::SyntaxError.severity(), Severity::Error);
+        assert_eq!(ProblemKind::InvalidName.severity(), Severity::Error);
+        assert_eq!(ProblemKind::DuplicateKey.severity(), Severity::Warning);
+    }
+}
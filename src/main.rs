@@ -6,8 +6,19 @@
 
 mod commands;
 mod config;
+mod diff;
+mod dotenv;
+mod filter;
+mod input;
 mod interactive;
+mod lint;
 mod models;
+mod output;
+mod path_list;
+mod redact;
+mod snapshot;
+mod sort;
+mod template;
 mod utils;
 mod variables;
 
@@ -71,8 +82,9 @@ mod tests {
         assert_eq!(
             args.command,
             Commands::Get(GetArgs {
-                key: "PATH".to_string(),
-                no_similar_names: false
+                keys: vec!["PATH".to_string()],
+                no_similar_names: false,
+                ..Default::default()
             })
         );
     }
@@ -83,16 +95,79 @@ mod tests {
         assert_eq!(
             args.command,
             Commands::Get(GetArgs {
-                key: "PATH".to_string(),
-                no_similar_names: true
+                keys: vec!["PATH".to_string()],
+                no_similar_names: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_command_with_multiple_keys() {
+        let args = Cli::parse_from([
+            "envfetch",
+            "get",
+            "HOME",
+            "PATH",
+            "--default",
+            "none",
+            "--raw",
+            "--allow-missing",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Get(GetArgs {
+                keys: vec!["HOME".to_string(), "PATH".to_string()],
+                default: Some("none".to_string()),
+                raw: true,
+                allow_missing: true,
+                ..Default::default()
             })
         );
     }
 
+    #[test]
+    fn test_path_command() {
+        let args = Cli::parse_from([
+            "envfetch", "path", "append", "PATH", "/opt/bin", "/usr/bin", "--", "echo",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Path(PathArgs {
+                command: PathCommands::Append(PathEditArgs {
+                    key: "PATH".to_string(),
+                    paths: vec!["/opt/bin".to_string(), "/usr/bin".to_string()],
+                    global: false,
+                    dry_run: false,
+                    reveal: false,
+                    process: vec!["echo".to_string()],
+                    run: RunArgs::default(),
+                }),
+            })
+        );
+        let args = Cli::parse_from(["envfetch", "path", "list", "PATH"]);
+        assert_eq!(
+            args.command,
+            Commands::Path(PathArgs {
+                command: PathCommands::List(PathVarArgs {
+                    key: "PATH".to_string(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn test_path_append_requires_process_or_global() {
+        let result = Cli::try_parse_from(["envfetch", "path", "append", "PATH", "/opt/bin"]);
+        assert!(result.is_err());
+        let result = Cli::try_parse_from(["envfetch", "path", "dedupe", "PATH", "--global"]);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_print_command() {
         let args = Cli::parse_from(["envfetch", "print"]);
-        assert_eq!(args.command, Commands::Print(PrintArgs { format: None }));
+        assert_eq!(args.command, Commands::Print(PrintArgs::default()));
     }
 
     #[test]
@@ -101,11 +176,88 @@ mod tests {
         assert_eq!(
             args.command,
             Commands::Print(PrintArgs {
-                format: Some("{name}: \"{value}\"".to_owned())
+                format: Some("{name}: \"{value}\"".to_owned()),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_print_command_with_output() {
+        let args = Cli::parse_from(["envfetch", "print", "--output", "json"]);
+        assert_eq!(
+            args.command,
+            Commands::Print(PrintArgs {
+                output: Some(OutputFormat::Json),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_print_command_with_output_and_format() {
+        let result = Cli::try_parse_from(["envfetch", "print", "-o", "csv", "-f", "{name}"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_print_command_with_filters() {
+        let args = Cli::parse_from([
+            "envfetch",
+            "print",
+            "--match",
+            "AWS_*",
+            "--regex",
+            "^CARGO",
+            "--prefix",
+            "XDG_",
+            "--value-contains",
+            "/home",
+            "--invert",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Print(PrintArgs {
+                filter: FilterArgs {
+                    globs: vec!["AWS_*".to_string()],
+                    regexes: vec!["^CARGO".to_string()],
+                    prefixes: vec!["XDG_".to_string()],
+                    value_contains: vec!["/home".to_string()],
+                    invert: true,
+                },
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_print_command_with_sorting() {
+        let args = Cli::parse_from([
+            "envfetch",
+            "print",
+            "--sort",
+            "length",
+            "--reverse",
+            "--group-by-prefix",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Print(PrintArgs {
+                sort: Some(SortKey::Length),
+                reverse: true,
+                group_by_prefix: true,
+                ..Default::default()
             })
         );
     }
 
+    #[test]
+    fn test_print_command_group_by_prefix_with_output() {
+        let result =
+            Cli::try_parse_from(["envfetch", "print", "--group-by-prefix", "--output", "json"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_init_config() {
         let args = Cli::parse_from(["envfetch", "init-config"]);
@@ -119,9 +271,11 @@ mod tests {
             args.command,
             Commands::Set(SetArgs {
                 global: false,
-                key: "VAR".to_string(),
-                value: "VALUE".to_string(),
-                process: vec!["npm".to_string(), "run".to_string()]
+                dry_run: false,
+                reveal: false,
+                variables: vec!["VAR".to_string(), "VALUE".to_string()],
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
             })
         );
     }
@@ -133,9 +287,11 @@ mod tests {
             args.command,
             Commands::Set(SetArgs {
                 global: true,
-                key: "VAR".to_string(),
-                value: "VALUE".to_string(),
-                process: vec![]
+                dry_run: false,
+                reveal: false,
+                variables: vec!["VAR".to_string(), "VALUE".to_string()],
+                process: vec![],
+                run: RunArgs::default(),
             })
         );
     }
@@ -149,23 +305,37 @@ mod tests {
             args.command,
             Commands::Set(SetArgs {
                 global: true,
-                key: "VAR".to_string(),
-                value: "VALUE".to_string(),
-                process: vec!["npm".to_string(), "run".to_string()]
+                dry_run: false,
+                reveal: false,
+                variables: vec!["VAR".to_string(), "VALUE".to_string()],
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
             })
         );
     }
 
     #[test]
     fn test_add_command_simple() {
-        let args = Cli::parse_from(["envfetch", "add", "PATH", "./executable", "--", "npm", "run"]);
+        let args = Cli::parse_from([
+            "envfetch",
+            "add",
+            "PATH",
+            "./executable",
+            "--",
+            "npm",
+            "run",
+        ]);
         assert_eq!(
             args.command,
             Commands::Add(AddArgs {
                 global: false,
+                dry_run: false,
+                reveal: false,
                 key: "PATH".to_string(),
                 value: "./executable".to_string(),
-                process: vec!["npm".to_string(), "run".to_string()]
+                separator: None,
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
             })
         );
     }
@@ -177,9 +347,13 @@ mod tests {
             args.command,
             Commands::Add(AddArgs {
                 global: true,
+                dry_run: false,
+                reveal: false,
                 key: "PATH".to_string(),
                 value: "./executable".to_string(),
-                process: vec![]
+                separator: None,
+                process: vec![],
+                run: RunArgs::default(),
             })
         );
     }
@@ -200,11 +374,320 @@ mod tests {
             args.command,
             Commands::Add(AddArgs {
                 global: true,
+                dry_run: false,
+                reveal: false,
                 key: "PATH".to_string(),
                 value: "./executable".to_string(),
-                process: vec!["npm".to_string(), "run".to_string()]
+                separator: None,
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_prepend_command_with_separator() {
+        let args = Cli::parse_from([
+            "envfetch",
+            "prepend",
+            "RUSTFLAGS",
+            "-Dwarnings",
+            "--separator",
+            " ",
+            "--",
+            "cargo",
+            "build",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Prepend(AddArgs {
+                global: false,
+                dry_run: false,
+                reveal: false,
+                key: "RUSTFLAGS".to_string(),
+                value: "-Dwarnings".to_string(),
+                separator: Some(" ".to_string()),
+                process: vec!["cargo".to_string(), "build".to_string()],
+                run: RunArgs::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_command_multiple_variables() {
+        let args = Cli::parse_from(["envfetch", "set", "A=1", "B=2", "--", "npm", "run"]);
+        assert_eq!(
+            args.command,
+            Commands::Set(SetArgs {
+                global: false,
+                dry_run: false,
+                reveal: false,
+                variables: vec!["A=1".to_string(), "B=2".to_string()],
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_delete_command_multiple_variables() {
+        let args = Cli::parse_from(["envfetch", "delete", "A", "B", "C", "--global"]);
+        assert_eq!(
+            args.command,
+            Commands::Delete(DeleteArgs {
+                keys: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                global: true,
+                dry_run: false,
+                reveal: false,
+                process: vec![],
+                run: RunArgs::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_set_command_without_shell() {
+        let args = Cli::parse_from([
+            "envfetch",
+            "set",
+            "VAR=VALUE",
+            "--no-shell",
+            "--exec",
+            "--",
+            "printf",
+            "%s",
+            "a b",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Set(SetArgs {
+                global: false,
+                dry_run: false,
+                reveal: false,
+                variables: vec!["VAR=VALUE".to_string()],
+                run: RunArgs {
+                    no_shell: true,
+                    exec: true,
+                    ..Default::default()
+                },
+                process: vec!["printf".to_string(), "%s".to_string(), "a b".to_string()]
+            })
+        );
+        let result =
+            Cli::try_parse_from(["envfetch", "load", "--shell", "--no-shell", "--", "echo"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_command_with_clear_env() {
+        let args = Cli::parse_from([
+            "envfetch",
+            "load",
+            "--clear-env",
+            "--keep",
+            "HOME,PATH",
+            "--keep-prefix",
+            "CARGO_",
+            "--",
+            "make",
+        ]);
+        let Commands::Load(args) = args.command else {
+            panic!("Expected load command");
+        };
+        assert_eq!(
+            args.run,
+            RunArgs {
+                clear_env: true,
+                keep: vec!["HOME".to_string(), "PATH".to_string()],
+                keep_prefix: vec!["CARGO_".to_string()],
+                ..Default::default()
+            }
+        );
+        // Variables can be kept only in cleared environment
+        let result = Cli::try_parse_from(["envfetch", "load", "--keep", "HOME", "--", "make"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_command_with_files() {
+        let args = Cli::parse_from(["envfetch", "load", "--global"]);
+        let Commands::Load(args) = args.command else {
+            panic!("Expected load command");
+        };
+        // Default file is chosen when loading
+        assert!(args.file.is_empty());
+
+        let args = Cli::parse_from([
+            "envfetch",
+            "load",
+            "--file",
+            "-",
+            "--env-string",
+            "A=1",
+            "--global",
+        ]);
+        let Commands::Load(args) = args.command else {
+            panic!("Expected load command");
+        };
+        assert_eq!(args.file, vec!["-".to_string()]);
+        assert_eq!(args.env_string, vec!["A=1".to_string()]);
+
+        let args = Cli::parse_from([
+            "envfetch",
+            "load",
+            "-f",
+            ".env",
+            "--file",
+            ".env.test",
+            "--explain",
+        ]);
+        let Commands::Load(args) = args.command else {
+            panic!("Expected load command");
+        };
+        assert_eq!(args.file, vec![".env".to_string(), ".env.test".to_string()]);
+        assert!(args.explain);
+        // Explanation doesn't start process
+        assert!(Cli::try_parse_from(["envfetch", "load", "--explain", "--", "echo"]).is_err());
+
+        let args = Cli::parse_from(["envfetch", "load", "--cascade", "test", "--global"]);
+        let Commands::Load(args) = args.command else {
+            panic!("Expected load command");
+        };
+        assert_eq!(args.cascade, Some("test".to_string()));
+        assert_eq!(args.format, None);
+
+        let args = Cli::parse_from(["envfetch", "load", "--format", "yaml", "--global"]);
+        let Commands::Load(args) = args.command else {
+            panic!("Expected load command");
+        };
+        assert_eq!(args.format, Some(InputFormat::Yaml));
+        // Cascade mode chooses files itself
+        let result = Cli::try_parse_from([
+            "envfetch",
+            "load",
+            "--cascade",
+            "test",
+            "--file",
+            ".env",
+            "--global",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_dry_run_doesnt_require_process() {
+        let args = Cli::parse_from(["envfetch", "set", "--dry-run", "VAR=value"]);
+        let Commands::Set(args) = args.command else {
+            panic!("Expected set command");
+        };
+        assert!(args.dry_run);
+        assert!(args.process.is_empty());
+        for command in [
+            vec!["envfetch", "add", "--dry-run", "VAR", "value"],
+            vec!["envfetch", "delete", "--dry-run", "VAR"],
+            vec!["envfetch", "load", "--dry-run"],
+            vec!["envfetch", "path", "dedupe", "--dry-run", "PATH"],
+        ] {
+            assert!(Cli::try_parse_from(&command).is_ok(), "{:?}", command);
+        }
+        assert!(Cli::try_parse_from(["envfetch", "delete", "VAR"]).is_err());
+    }
+
+    #[test]
+    fn test_lint_command() {
+        let args = Cli::parse_from(["envfetch", "lint"]);
+        assert_eq!(
+            args.command,
+            Commands::Lint(LintArgs {
+                files: vec![".env".to_string()],
+                output: ReportFormat::Human,
+                strict: false,
+            })
+        );
+        let args = Cli::parse_from([
+            "envfetch", "lint", "a.env", "b.env", "-o", "json", "--strict",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Lint(LintArgs {
+                files: vec!["a.env".to_string(), "b.env".to_string()],
+                output: ReportFormat::Json,
+                strict: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_command_selection() {
+        let args = Cli::parse_from([
+            "envfetch", "export", "-", "-m", "APP_*", "--regex", "^DB_", "-x", "*_SECRET",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Export(ExportArgs {
+                file_name: "-".to_string(),
+                globs: vec!["APP_*".to_string()],
+                regexes: vec!["^DB_".to_string()],
+                excludes: vec!["*_SECRET".to_string()],
+                ..Default::default()
+            })
+        );
+        assert!(Cli::try_parse_from(["envfetch", "export", "out.env"]).is_err());
+        assert!(Cli::try_parse_from(["envfetch", "export", "out.env", "--all"]).is_ok());
+        assert!(
+            Cli::try_parse_from([
+                "envfetch",
+                "export",
+                "out.env",
+                "--all",
+                "--merge",
+                "--no-clobber"
+            ])
+            .is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["envfetch", "export", "out.env", "--all", "--", "VAR"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_snapshot_command() {
+        let args = Cli::parse_from(["envfetch", "snapshot", "diff", "before"]);
+        assert_eq!(
+            args.command,
+            Commands::Snapshot(SnapshotArgs {
+                command: SnapshotCommands::Diff(SnapshotDiffArgs {
+                    from: "before".to_string(),
+                    to: "current".to_string(),
+                    reveal: false,
+                })
+            })
+        );
+        let args = Cli::parse_from([
+            "envfetch",
+            "snapshot",
+            "restore",
+            "base",
+            "--no-shell",
+            "--",
+            "cargo",
+            "build",
+        ]);
+        assert_eq!(
+            args.command,
+            Commands::Snapshot(SnapshotArgs {
+                command: SnapshotCommands::Restore(SnapshotRestoreArgs {
+                    name: "base".to_string(),
+                    run: RunArgs {
+                        no_shell: true,
+                        ..Default::default()
+                    },
+                    process: vec!["cargo".to_string(), "build".to_string()],
+                })
             })
         );
+        assert!(Cli::try_parse_from(["envfetch", "snapshot", "restore", "base"]).is_err());
+        assert!(Cli::try_parse_from(["envfetch", "snapshot", "save"]).is_err());
     }
 
     #[test]
@@ -213,9 +696,12 @@ mod tests {
         assert_eq!(
             args.command,
             Commands::Delete(DeleteArgs {
-                key: "VAR".to_string(),
+                keys: vec!["VAR".to_string()],
                 global: false,
-                process: vec!["npm".to_string(), "run".to_string()]
+                dry_run: false,
+                reveal: false,
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
             })
         );
     }
@@ -226,9 +712,12 @@ mod tests {
         assert_eq!(
             args.command,
             Commands::Delete(DeleteArgs {
-                key: "VAR".to_string(),
+                keys: vec!["VAR".to_string()],
                 global: true,
-                process: vec![]
+                dry_run: false,
+                reveal: false,
+                process: vec![],
+                run: RunArgs::default(),
             })
         );
     }
@@ -239,9 +728,12 @@ mod tests {
         assert_eq!(
             args.command,
             Commands::Delete(DeleteArgs {
-                key: "VAR".to_string(),
+                keys: vec!["VAR".to_string()],
                 global: true,
-                process: vec!["npm".to_string(), "run".to_string()]
+                dry_run: false,
+                reveal: false,
+                process: vec!["npm".to_string(), "run".to_string()],
+                run: RunArgs::default(),
             })
         );
     }
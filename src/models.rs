@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::fmt::Display;
 
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 
 #[derive(Parser)]
@@ -24,17 +24,19 @@ pub struct Cli {
 }
 
 /// All tool's commands
-#[derive(Subcommand, Debug, PartialEq, Eq)]
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
 pub enum Commands {
     /// Open envfetch in interactive mode with TUI.
     Interactive,
     /// Print value of environment variable.
     Get(GetArgs),
-    /// Set environment variable and optionally run given process.
+    /// Set environment variables and optionally run given process.
     Set(SetArgs),
     /// Add value to the end of environment variable and optionally run given process.
     Add(AddArgs),
-    /// Delete environment variable and optionally run given process.
+    /// Add value to the beginning of environment variable and optionally run given process.
+    Prepend(AddArgs),
+    /// Delete environment variables and optionally run given process.
     Delete(DeleteArgs),
     /// Load environment variables from dotenv file and optionally run given process.
     Load(LoadArgs),
@@ -42,62 +44,250 @@ pub enum Commands {
     Print(PrintArgs),
     /// Initialize config file.
     InitConfig,
-    /// Export variable to .env file
+    /// Export variables to file or standard output.
     Export(ExportArgs),
+    /// Work with PATH-style variables, containing list of paths.
+    Path(PathArgs),
+    /// Check dotenv files for problems.
+    Lint(LintArgs),
+    /// Save, compare and restore snapshots of environment.
+    Snapshot(SnapshotArgs),
 }
 
 /// Args for print command
-#[derive(Args, Debug, PartialEq, Eq)]
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
 pub struct PrintArgs {
     /// Set custom format, by default {name} = "{value}" is used.
     #[arg(long, short)]
     pub format: Option<String>,
+    /// Print variables in structured format instead of custom format
+    #[arg(long, short, value_enum, conflicts_with = "format")]
+    pub output: Option<OutputFormat>,
+    /// Filters for variables
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    /// Sort variables by given key, by default order given by OS is used
+    #[arg(long, short, value_enum)]
+    pub sort: Option<SortKey>,
+    /// Reverse order of variables
+    #[arg(long)]
+    pub reverse: bool,
+    /// Split variables into sections by prefix of their names, e.g. CARGO_
+    #[arg(long, conflicts_with = "output")]
+    pub group_by_prefix: bool,
+    /// Show values of secret-looking variables instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+}
+
+/// Keys for sorting variables
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Sort by variable's name
+    Name,
+    /// Sort by variable's value
+    Value,
+    /// Sort by length of variable's value
+    Length,
+}
+
+/// Rules for validating names of variables
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NameValidation {
+    /// Reject only names that can't be set by OS or contain whitespace or control characters
+    #[default]
+    Permissive,
+    /// Allow only POSIX portable names with letters, digits and underscores, not starting with digit
+    Strict,
+}
+
+/// Args for filtering variables
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterArgs {
+    /// Show only variables with names matching glob pattern, e.g. 'AWS_*'
+    #[arg(long = "match", short = 'm', value_name = "GLOB")]
+    pub globs: Vec<String>,
+    /// Show only variables with names matching regular expression
+    #[arg(long = "regex", short = 'r', value_name = "REGEX")]
+    pub regexes: Vec<String>,
+    /// Show only variables with names starting with prefix
+    #[arg(long = "prefix", short = 'p', value_name = "PREFIX")]
+    pub prefixes: Vec<String>,
+    /// Show only variables with values containing given string
+    #[arg(long, value_name = "STRING")]
+    pub value_contains: Vec<String>,
+    /// Show only variables that don't match filters
+    #[arg(long, short = 'v')]
+    pub invert: bool,
+}
+
+/// Structured formats for list of variables
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// JSON object with variables' names as keys
+    Json,
+    /// YAML mapping with variables' names as keys
+    Yaml,
+    /// TOML table with variables' names as keys
+    Toml,
+    /// CSV with name and value columns
+    Csv,
+    /// One JSON object with name and value per line
+    Ndjson,
+}
+
+/// Formats of files with variables
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Dotenv file with KEY=value lines
+    Dotenv,
+    /// JSON object or array of objects with name and value
+    Json,
+    /// YAML mapping or sequence of mappings with name and value
+    Yaml,
+    /// TOML table
+    Toml,
+    /// Shell script with export KEY=value lines
+    Shell,
+}
+
+/// Formats of exported files
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Dotenv file with KEY=value lines
+    Dotenv,
+    /// JSON object with variables' names as keys
+    Json,
+    /// YAML mapping with variables' names as keys
+    Yaml,
+    /// TOML table with variables' names as keys
+    Toml,
+    /// POSIX shell script with export KEY=value lines
+    Shell,
+    /// PowerShell script with $env:KEY = 'value' lines
+    Powershell,
+    /// File for docker run --env-file, values can't span several lines
+    DockerEnv,
+    /// File for EnvironmentFile option of systemd units
+    Systemd,
 }
 
 /// Args for get command
-#[derive(Args, Debug, PartialEq, Eq)]
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
 pub struct GetArgs {
-    /// Environment variable name
-    #[arg(required = true)]
-    pub key: String,
+    /// Environment variables' names
+    #[arg(required = true, value_name = "KEY")]
+    pub keys: Vec<String>,
     /// Disable showing similar variables' names if variable not found
     #[arg(long, short = 's', default_value = "false")]
     pub no_similar_names: bool,
+    /// Show value even if variable looks like secret
+    #[arg(long)]
+    pub reveal: bool,
+    /// Value to print for variables that aren't set
+    #[arg(long, value_name = "VALUE")]
+    pub default: Option<String>,
+    /// Print values as is, without quoting
+    #[arg(long)]
+    pub raw: bool,
+    /// Print raw values separated by NUL character
+    #[arg(long, short = '0')]
+    pub null: bool,
+    /// Exit successfully if only some of variables aren't set
+    #[arg(long)]
+    pub allow_missing: bool,
+}
+
+/// Options for running process
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunArgs {
+    /// Start process directly, without system shell, passing arguments as is
+    #[arg(long, conflicts_with = "shell")]
+    pub no_shell: bool,
+    /// Start process using system shell, even if it's disabled in config
+    #[arg(long)]
+    pub shell: bool,
+    /// Replace envfetch with process instead of starting child process (Unix only)
+    #[arg(long)]
+    pub exec: bool,
+    /// Start process with empty environment, containing only variables set by command
+    #[arg(long)]
+    pub clear_env: bool,
+    /// Names of variables to keep in cleared environment
+    #[arg(
+        long,
+        value_name = "NAME,...",
+        value_delimiter = ',',
+        requires = "clear_env"
+    )]
+    pub keep: Vec<String>,
+    /// Prefixes of names of variables to keep in cleared environment
+    #[arg(long, value_name = "PREFIX", requires = "clear_env")]
+    pub keep_prefix: Vec<String>,
 }
 
 /// Args for load command
-#[derive(Args, Debug, PartialEq, Eq)]
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
 pub struct LoadArgs {
     /// Globally set variable
     #[arg(required = false, long, short)]
     pub global: bool,
-    /// Process to start, not required if --global flag is set
+    /// Show changes, which would be made, without applying them or starting process
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Show values of secret-looking variables in dry run output instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start, not required if --global, --dry-run or --explain flag is set
     #[arg(
         last = true,
-        required_unless_present = "global",
+        required_unless_present_any = ["global", "dry_run", "explain"],
         allow_hyphen_values = true,
         num_args = 1..
     )]
     pub process: Vec<String>,
-    /// Relative or absolute path to file to read variables from.
-    /// Note that it must be in .env format
-    #[arg(long, short, default_value = ".env")]
-    pub file: String,
+    /// Relative or absolute path to file to read variables from, or - to read standard input.
+    /// Can be repeated, later files override earlier ones. By default .env is used
+    #[arg(long, short, conflicts_with = "cascade")]
+    pub file: Vec<String>,
+    /// Dotenv content to read variables from, e.g. 'A=1\nB=2'. Overrides variables from files
+    #[arg(long, value_name = "CONTENT")]
+    pub env_string: Vec<String>,
+    /// Load .env, .env.local, .env.ENV and .env.ENV.local in this order, skipping missing files
+    #[arg(long, value_name = "ENV")]
+    pub cascade: Option<String>,
+    /// Print which file each variable was loaded from, without applying variables or starting process
+    #[arg(long, conflicts_with_all = ["global", "process"])]
+    pub explain: bool,
+    /// Format of files, by default it's detected by extension and dotenv is used for unknown ones
+    #[arg(long, value_enum)]
+    pub format: Option<InputFormat>,
 }
 
 /// Args for set command
-#[derive(Args, Debug, PartialEq, Eq)]
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
 pub struct SetArgs {
-    /// Environment variable name
-    #[arg(required = true)]
-    pub key: String,
-    /// Value for environment variable
-    #[arg(required = true)]
-    pub value: String,
+    /// Variables to set as NAME=VALUE pairs, or single name followed by value
+    #[arg(required = true, value_name = "NAME=VALUE")]
+    pub variables: Vec<String>,
     /// Globally set variable
     #[arg(required = false, long, short)]
     pub global: bool,
-    /// Process to start, not required if --global flag is set
+    /// Show changes, which would be made, without applying them or starting process
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Show values of secret-looking variables in dry run output instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start, not required if --global or --dry-run flag is set
     // #[arg(
     //     last = true,
     //     required_unless_present = "global",
@@ -115,48 +305,161 @@ pub struct SetArgs {
     // )]
     #[arg(
         last = true,
-        required_unless_present = "global",
+        required_unless_present_any = ["global", "dry_run"],
         allow_hyphen_values = true,
         num_args = 1..
     )]
     pub process: Vec<String>,
 }
 
-/// Args for add command
-#[derive(Args, Debug, PartialEq, Eq)]
+/// Args for add and prepend commands
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
 pub struct AddArgs {
     /// Environment variable name
     #[arg(required = true)]
     pub key: String,
-    /// Value for add to the end of environment variable
-    #[arg(required = true)]
+    /// Value for add to environment variable
+    #[arg(required = true, allow_hyphen_values = true)]
     pub value: String,
+    /// Separator between current and added value, used only if variable isn't empty
+    #[arg(long)]
+    pub separator: Option<String>,
     /// Globally set variable
     #[arg(required = false, long, short)]
     pub global: bool,
-    /// Process to start, not required if --global flag is set
+    /// Show changes, which would be made, without applying them or starting process
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Show values of secret-looking variables in dry run output instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start, not required if --global or --dry-run flag is set
     #[arg(
         last = true,
-        required_unless_present = "global",
+        required_unless_present_any = ["global", "dry_run"],
         allow_hyphen_values = true,
         num_args = 1..
     )]
     pub process: Vec<String>,
 }
 
-/// Args for delete command
-#[derive(Args, Debug, PartialEq, Eq)]
-pub struct DeleteArgs {
+/// Args for path command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct PathArgs {
+    /// Operation with list of paths
+    #[command(subcommand)]
+    pub command: PathCommands,
+}
+
+/// Operations with PATH-style variables
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum PathCommands {
+    /// Print entries of variable, one per line.
+    List(PathVarArgs),
+    /// Add paths to the end of variable and optionally run given process.
+    Append(PathEditArgs),
+    /// Add paths to the beginning of variable and optionally run given process.
+    Prepend(PathEditArgs),
+    /// Remove paths from variable and optionally run given process.
+    Remove(PathEditArgs),
+    /// Remove duplicated entries from variable and optionally run given process.
+    Dedupe(PathDedupeArgs),
+    /// Check that all entries of variable are existing directories.
+    Check(PathVarArgs),
+}
+
+/// Args for path list and path check commands
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct PathVarArgs {
+    /// Environment variable name
+    #[arg(required = true)]
+    pub key: String,
+}
+
+/// Args for path append, prepend and remove commands
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct PathEditArgs {
+    /// Environment variable name
+    #[arg(required = true)]
+    pub key: String,
+    /// Paths to add or remove
+    #[arg(required = true, value_name = "PATH")]
+    pub paths: Vec<String>,
+    /// Globally set variable
+    #[arg(required = false, long, short)]
+    pub global: bool,
+    /// Show changes, which would be made, without applying them or starting process
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Show values of secret-looking variables in dry run output instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start, not required if --global or --dry-run flag is set
+    #[arg(
+        last = true,
+        required_unless_present_any = ["global", "dry_run"],
+        allow_hyphen_values = true,
+        num_args = 1..
+    )]
+    pub process: Vec<String>,
+}
+
+/// Args for path dedupe command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct PathDedupeArgs {
     /// Environment variable name
     #[arg(required = true)]
     pub key: String,
     /// Globally set variable
     #[arg(required = false, long, short)]
     pub global: bool,
-    /// Process to start, not required if --global flag is set
+    /// Show changes, which would be made, without applying them or starting process
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Show values of secret-looking variables in dry run output instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start, not required if --global or --dry-run flag is set
     #[arg(
         last = true,
-        required_unless_present = "global",
+        required_unless_present_any = ["global", "dry_run"],
+        allow_hyphen_values = true,
+        num_args = 1..
+    )]
+    pub process: Vec<String>,
+}
+
+/// Args for delete command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct DeleteArgs {
+    /// Environment variables' names
+    #[arg(required = true, value_name = "KEY")]
+    pub keys: Vec<String>,
+    /// Globally set variable
+    #[arg(required = false, long, short)]
+    pub global: bool,
+    /// Show changes, which would be made, without applying them or starting process
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Show values of secret-looking variables in dry run output instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start, not required if --global or --dry-run flag is set
+    #[arg(
+        last = true,
+        required_unless_present_any = ["global", "dry_run"],
         allow_hyphen_values = true,
         num_args = 1..
     )]
@@ -164,18 +467,170 @@ pub struct DeleteArgs {
 }
 
 /// Args for export command
-#[derive(Args, Debug, PartialEq, Eq)]
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
 pub struct ExportArgs {
-    /// File name to be exported as
-    #[arg(required = true)]
+    /// File to export variables to, or - to print them
+    #[arg(required = true, value_name = "FILE")]
     pub file_name: String,
     /// Environment variable(s) name
     #[arg(
-        required = true,
+        required_unless_present_any = ["all", "globs", "regexes"],
         last = true,
         num_args = 1..
     )]
     pub keys: Vec<String>,
+    /// Export all environment variables
+    #[arg(long, conflicts_with_all = ["keys", "globs", "regexes"])]
+    pub all: bool,
+    /// Export variables with names matching glob pattern, e.g. 'APP_*'
+    #[arg(long = "match", short = 'm', value_name = "GLOB")]
+    pub globs: Vec<String>,
+    /// Export variables with names matching regular expression
+    #[arg(long = "regex", short = 'r', value_name = "REGEX")]
+    pub regexes: Vec<String>,
+    /// Don't export variables with names matching glob pattern
+    #[arg(long = "exclude", short = 'x', value_name = "GLOB")]
+    pub excludes: Vec<String>,
+    /// Write placeholders instead of values of secret-looking variables
+    #[arg(long)]
+    pub placeholders: bool,
+    /// Format of exported file, by default it's detected from extension
+    #[arg(long, short, value_enum)]
+    pub output: Option<ExportFormat>,
+    /// Update existing dotenv file, keeping its comments and other variables
+    #[arg(long, conflicts_with = "no_clobber")]
+    pub merge: bool,
+    /// Fail if file already exists instead of overwriting it
+    #[arg(long)]
+    pub no_clobber: bool,
+}
+
+/// Args for lint command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct LintArgs {
+    /// Dotenv files to check
+    #[arg(default_value = ".env", value_name = "FILE")]
+    pub files: Vec<String>,
+    /// Format of report
+    #[arg(long, short, value_enum, default_value = "human")]
+    pub output: ReportFormat,
+    /// Fail if there are warnings, not only errors
+    #[arg(long)]
+    pub strict: bool,
+}
+
+/// Args for snapshot command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotArgs {
+    /// Operation with snapshots
+    #[command(subcommand)]
+    pub command: SnapshotCommands,
+}
+
+/// Operations with snapshots of environment
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotCommands {
+    /// Save current environment as snapshot.
+    Save(SnapshotSaveArgs),
+    /// List saved snapshots.
+    List,
+    /// Print variables from snapshot.
+    Show(SnapshotShowArgs),
+    /// Show changes between two snapshots or snapshot and current environment.
+    Diff(SnapshotDiffArgs),
+    /// Start process with environment from snapshot.
+    Restore(SnapshotRestoreArgs),
+}
+
+/// Args for snapshot save command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotSaveArgs {
+    /// Name of snapshot
+    #[arg(required = true)]
+    pub name: String,
+    /// Overwrite snapshot if it already exists
+    #[arg(long, short)]
+    pub force: bool,
+}
+
+/// Args for snapshot show command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotShowArgs {
+    /// Name of snapshot
+    #[arg(required = true)]
+    pub name: String,
+    /// Print variables in structured format
+    #[arg(long, short, value_enum)]
+    pub output: Option<OutputFormat>,
+    /// Show values of secret-looking variables instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+}
+
+/// Args for snapshot diff command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotDiffArgs {
+    /// Name of snapshot to compare with
+    #[arg(required = true)]
+    pub from: String,
+    /// Name of compared snapshot, or current for current environment
+    #[arg(default_value = "current")]
+    pub to: String,
+    /// Show values of secret-looking variables instead of masking them
+    #[arg(long)]
+    pub reveal: bool,
+}
+
+/// Args for snapshot restore command
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotRestoreArgs {
+    /// Name of snapshot
+    #[arg(required = true)]
+    pub name: String,
+    /// Options for running process
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Process to start
+    #[arg(
+        last = true,
+        required = true,
+        allow_hyphen_values = true,
+        num_args = 1..
+    )]
+    pub process: Vec<String>,
+}
+
+/// Formats of lint report
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// One line per problem with file, line and column
+    Human,
+    /// JSON array of problems
+    Json,
+}
+
+impl Commands {
+    /// Get options for running process, if command can run it
+    pub fn run_args_mut(&mut self) -> Option<&mut RunArgs> {
+        match self {
+            Commands::Set(args) => Some(&mut args.run),
+            Commands::Add(args) | Commands::Prepend(args) => Some(&mut args.run),
+            Commands::Delete(args) => Some(&mut args.run),
+            Commands::Load(args) => Some(&mut args.run),
+            Commands::Path(args) => match &mut args.command {
+                PathCommands::Append(args)
+                | PathCommands::Prepend(args)
+                | PathCommands::Remove(args) => Some(&mut args.run),
+                PathCommands::Dedupe(args) => Some(&mut args.run),
+                PathCommands::List(_) | PathCommands::Check(_) => None,
+            },
+            Commands::Snapshot(args) => match &mut args.command {
+                SnapshotCommands::Restore(args) => Some(&mut args.run),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -187,6 +642,11 @@ pub enum ErrorKind {
     FileError(String),
     CannotFindVariable(String, bool),
     NameValidationError(String),
+    SerializationError(String),
+    PatternError(String),
+    PathListError(String),
+    CyclicReference(String),
+    SnapshotError(String),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -230,14 +690,31 @@ impl Display for ErrorKind {
             ErrorKind::FileError(err) => write!(f, "File error: {}", err),
             ErrorKind::CannotFindVariable(name, _) => write!(f, "Can't find variable: {}", name),
             ErrorKind::NameValidationError(err) => write!(f, "Name validation error: {}", err),
+            ErrorKind::SerializationError(err) => write!(f, "Serialization error: {}", err),
+            ErrorKind::PatternError(err) => write!(f, "Invalid pattern: {}", err),
+            ErrorKind::PathListError(err) => write!(f, "Invalid list of paths: {}", err),
+            ErrorKind::CyclicReference(cycle) => {
+                write!(f, "Cyclic reference between variables: {}", cycle)
+            }
+            ErrorKind::SnapshotError(err) => write!(f, "Snapshot error: {}", err),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
 pub struct Config {
     /// Format, used to print variables using print command
     pub print_format: Option<String>,
+    /// Default order of variables in print command
+    pub print_sort: Option<SortKey>,
+    /// Glob patterns for names of variables, which values are masked
+    pub secret_patterns: Option<Vec<String>>,
+    /// Mask values that look like random tokens
+    pub mask_high_entropy: Option<bool>,
+    /// Start processes without system shell by default
+    pub no_shell: Option<bool>,
+    /// Rules for validating names of variables
+    pub name_validation: Option<NameValidation>,
 }
 
 #[cfg(test)]
@@ -272,6 +749,26 @@ mod tests {
                 ErrorKind::NameValidationError("Variable name cannot be empty".to_string()),
                 "Name validation error: Variable name cannot be empty",
             ),
+            (
+                ErrorKind::SerializationError("Invalid key".to_string()),
+                "Serialization error: Invalid key",
+            ),
+            (
+                ErrorKind::PatternError("[".to_string()),
+                "Invalid pattern: [",
+            ),
+            (
+                ErrorKind::PathListError("separator in path".to_string()),
+                "Invalid list of paths: separator in path",
+            ),
+            (
+                ErrorKind::CyclicReference("A -> B -> A".to_string()),
+                "Cyclic reference between variables: A -> B -> A",
+            ),
+            (
+                ErrorKind::SnapshotError("Snapshot base doesn't exist".to_string()),
+                "Snapshot error: Snapshot base doesn't exist",
+            ),
         ];
 
         for (error, expected) in test_cases {
@@ -0,0 +1,505 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use serde::ser::SerializeMap;
+
+use crate::dotenv::{self, Entry, Quote};
+use crate::models::{ErrorKind, ExportFormat, OutputFormat};
+use crate::template::shell_quote;
+
+/// List of variables serialized as map, keeping order of variables
+struct VariablesMap<'a>(&'a [(String, String)]);
+
+impl Serialize for VariablesMap<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (name, value) in self.0 {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Single variable, used for line-delimited formats
+#[derive(Serialize)]
+struct Variable<'a> {
+    name: &'a str,
+    value: &'a str,
+}
+
+/// Write list of variables to buffer in given format
+pub fn write_variables<W: Write>(
+    variables: &[(String, String)],
+    format: OutputFormat,
+    mut buffer: W,
+) -> Result<(), ErrorKind> {
+    let output = serialize_variables(variables, format)?;
+    buffer
+        .write_all(output.as_bytes())
+        .expect("Failed to write to buffer");
+    Ok(())
+}
+
+/// Serialize list of variables to string in given format
+pub fn serialize_variables(
+    variables: &[(String, String)],
+    format: OutputFormat,
+) -> Result<String, ErrorKind> {
+    let map = VariablesMap(variables);
+    let output = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&map)
+            .map(|json| json + "\n")
+            .map_err(serialization_error)?,
+        OutputFormat::Yaml => serde_yaml::to_string(&map).map_err(serialization_error)?,
+        OutputFormat::Toml => toml::to_string(&map).map_err(serialization_error)?,
+        OutputFormat::Csv => {
+            let mut output = String::from("name,value\n");
+            for (name, value) in variables {
+                output.push_str(&format!("{},{}\n", csv_field(name), csv_field(value)));
+            }
+            output
+        }
+        OutputFormat::Ndjson => {
+            let mut output = String::new();
+            for (name, value) in variables {
+                let line = serde_json::to_string(&Variable { name, value })
+                    .map_err(serialization_error)?;
+                output.push_str(&line);
+                output.push('\n');
+            }
+            output
+        }
+    };
+    Ok(output)
+}
+
+/// Detect format of exported file from its extension, dotenv is used by default
+pub fn detect_export_format(path: &str) -> ExportFormat {
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("json") => ExportFormat::Json,
+        Some("yaml" | "yml") => ExportFormat::Yaml,
+        Some("toml") => ExportFormat::Toml,
+        Some("sh" | "bash" | "zsh") => ExportFormat::Shell,
+        Some("ps1") => ExportFormat::Powershell,
+        _ => ExportFormat::Dotenv,
+    }
+}
+
+/// Serialize list of variables to content of file in given format
+pub fn serialize_export(
+    variables: &[(String, String)],
+    format: ExportFormat,
+) -> Result<String, ErrorKind> {
+    let structured = match format {
+        ExportFormat::Json => Some(OutputFormat::Json),
+        ExportFormat::Yaml => Some(OutputFormat::Yaml),
+        ExportFormat::Toml => Some(OutputFormat::Toml),
+        _ => None,
+    };
+    if let Some(structured) = structured {
+        return serialize_variables(variables, structured);
+    }
+    if format == ExportFormat::Dotenv {
+        return dotenv::write(variables);
+    }
+    let mut output = String::new();
+    for (name, value) in variables {
+        let line = match format {
+            ExportFormat::Shell => format!("export {}={}", name, shell_quote(value)),
+            ExportFormat::Powershell => format!(
+                "{} = '{}'",
+                powershell_variable(name),
+                value.replace('\'', "''")
+            ),
+            ExportFormat::DockerEnv => {
+                if value.contains(['\n', '\r']) {
+                    return Err(ErrorKind::SerializationError(format!(
+                        "Value of {} contains line break, which isn't supported in docker env files",
+                        name
+                    )));
+                }
+                format!("{}={}", name, value)
+            }
+            ExportFormat::Systemd => format!("{}={}", name, systemd_value(value)),
+            ExportFormat::Dotenv | ExportFormat::Json | ExportFormat::Yaml | ExportFormat::Toml => {
+                unreachable!("format is serialized above")
+            }
+        };
+        output.push_str(&line);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Merge variables into content of dotenv file.
+///
+/// Existing definitions are updated in place, keeping indentation, `export` prefix
+/// and comments, while new variables are appended to the end of file
+pub fn merge_dotenv(content: &str, variables: &[(String, String)]) -> Result<String, ErrorKind> {
+    let (bom, content) = match content.strip_prefix('\u{feff}') {
+        Some(content) => ("\u{feff}", content),
+        None => ("", content),
+    };
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let mut merged = String::from(bom);
+    let mut updated: HashSet<&str> = HashSet::new();
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let text = line.trim_end_matches(['\r', '\n']);
+        let entry = dotenv::parse_line(text, index + 1)?;
+        let variable = entry
+            .as_ref()
+            .and_then(|entry| variables.iter().find(|(name, _)| *name == entry.key));
+        match (entry, variable) {
+            (Some(entry), Some((name, value))) => {
+                let chars: Vec<char> = text.chars().collect();
+                let prefix: String = chars[..entry.column - 1].iter().collect();
+                merged.push_str(&format!(
+                    "{}{}{}",
+                    prefix,
+                    dotenv::format_entry(name, value)?,
+                    trailing_comment(&chars, &entry)
+                ));
+                merged.push_str(&line[text.len()..]);
+                updated.insert(name);
+            }
+            _ => merged.push_str(line),
+        }
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        merged.push_str(newline);
+    }
+    for (name, value) in variables {
+        if !updated.contains(name.as_str()) {
+            merged.push_str(&dotenv::format_entry(name, value)?);
+            merged.push_str(newline);
+        }
+    }
+    Ok(merged)
+}
+
+/// Get comment after value of entry, including whitespace before it
+fn trailing_comment(chars: &[char], entry: &Entry) -> String {
+    let equals = dotenv::skip_whitespace(chars, entry.column - 1 + entry.key.chars().count());
+    let start = dotenv::skip_whitespace(chars, equals + 1);
+    let comment_start = match entry.quote {
+        // Skip value and both quotes
+        Quote::Single | Quote::Double => start + entry.raw.chars().count() + 2,
+        // Like in parser, '#' at start of value is part of it
+        Quote::None => match (start + 1..chars.len())
+            .find(|&index| chars[index] == '#' && chars[index - 1].is_whitespace())
+        {
+            Some(mut index) => {
+                while index > equals + 1 && chars[index - 1].is_whitespace() {
+                    index -= 1;
+                }
+                index
+            }
+            None => chars.len(),
+        },
+    };
+    let comment: String = chars[comment_start..].iter().collect();
+    if comment.trim().is_empty() {
+        String::new()
+    } else {
+        comment
+    }
+}
+
+/// Check if value can be written without quotes in systemd files
+fn is_plain_value(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-@%+=:,./".contains(c))
+}
+
+/// Quote value for systemd's EnvironmentFile, double-quoted values may span several lines
+fn systemd_value(value: &str) -> String {
+    if !value.is_empty() && is_plain_value(value) {
+        return value.to_owned();
+    }
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reference to environment variable in PowerShell, using braces if name isn't simple
+fn powershell_variable(name: &str) -> String {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!("$env:{}", name)
+    } else {
+        let mut escaped = String::new();
+        for c in name.chars() {
+            if matches!(c, '{' | '}' | '`') {
+                escaped.push('`');
+            }
+            escaped.push(c);
+        }
+        format!("${{env:{}}}", escaped)
+    }
+}
+
+/// Convert serializer's error to tool's error
+fn serialization_error<E: std::fmt::Display>(err: E) -> ErrorKind {
+    ErrorKind::SerializationError(err.to_string())
+}
+
+/// Quote CSV field if it contains separator, quotes or line breaks
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> Vec<(String, String)> {
+        vec![
+            ("B_VAR".to_string(), "say \"hi\"".to_string()),
+            ("A_VAR".to_string(), "line1\nline2".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_serialize_json() {
+        let output = serialize_variables(&variables(), OutputFormat::Json).unwrap();
+        assert_eq!(
+            output,
+            "{\n  \"B_VAR\": \"say \\\"hi\\\"\",\n  \"A_VAR\": \"line1\\nline2\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_json_round_trip() {
+        let output = serialize_variables(&variables(), OutputFormat::Json).unwrap();
+        let parsed: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["B_VAR"], "say \"hi\"");
+        assert_eq!(parsed["A_VAR"], "line1\nline2");
+    }
+
+    #[test]
+    fn test_serialize_yaml_round_trip() {
+        let output = serialize_variables(&variables(), OutputFormat::Yaml).unwrap();
+        let parsed: std::collections::BTreeMap<String, String> =
+            serde_yaml::from_str(&output).unwrap();
+        assert_eq!(parsed["B_VAR"], "say \"hi\"");
+        assert_eq!(parsed["A_VAR"], "line1\nline2");
+        // Order of variables is kept
+        assert!(output.find("B_VAR").unwrap() < output.find("A_VAR").unwrap());
+    }
+
+    #[test]
+    fn test_serialize_toml_round_trip() {
+        let output = serialize_variables(&variables(), OutputFormat::Toml).unwrap();
+        let parsed: std::collections::BTreeMap<String, String> = toml::from_str(&output).unwrap();
+        assert_eq!(parsed["B_VAR"], "say \"hi\"");
+        assert_eq!(parsed["A_VAR"], "line1\nline2");
+    }
+
+    #[test]
+    fn test_serialize_csv() {
+        let output = serialize_variables(&variables(), OutputFormat::Csv).unwrap();
+        assert_eq!(
+            output,
+            "name,value\nB_VAR,\"say \"\"hi\"\"\"\nA_VAR,\"line1\nline2\"\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_csv_plain_field() {
+        assert_eq!(csv_field("plain value"), "plain value");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+
+    #[test]
+    fn test_serialize_ndjson() {
+        let output = serialize_variables(&variables(), OutputFormat::Ndjson).unwrap();
+        assert_eq!(
+            output,
+            "{\"name\":\"B_VAR\",\"value\":\"say \\\"hi\\\"\"}\n{\"name\":\"A_VAR\",\"value\":\"line1\\nline2\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_serialize_empty_list() {
+        assert_eq!(
+            serialize_variables(&[], OutputFormat::Json).unwrap(),
+            "{}\n"
+        );
+        assert_eq!(
+            serialize_variables(&[], OutputFormat::Csv).unwrap(),
+            "name,value\n"
+        );
+        assert_eq!(serialize_variables(&[], OutputFormat::Ndjson).unwrap(), "");
+    }
+
+    #[test]
+    fn test_write_variables() {
+        let mut buffer = vec![];
+        write_variables(&variables(), OutputFormat::Ndjson, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 2);
+    }
+
+    fn export(variables: &[(&str, &str)], format: ExportFormat) -> String {
+        let variables: Vec<(String, String)> = variables
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        serialize_export(&variables, format).unwrap()
+    }
+
+    #[test]
+    fn test_detect_export_format() {
+        assert_eq!(detect_export_format("out.env"), ExportFormat::Dotenv);
+        assert_eq!(detect_export_format(".env.local"), ExportFormat::Dotenv);
+        assert_eq!(detect_export_format("out.JSON"), ExportFormat::Json);
+        assert_eq!(detect_export_format("out.yml"), ExportFormat::Yaml);
+        assert_eq!(detect_export_format("out.toml"), ExportFormat::Toml);
+        assert_eq!(detect_export_format("env.sh"), ExportFormat::Shell);
+        assert_eq!(detect_export_format("env.ps1"), ExportFormat::Powershell);
+        assert_eq!(detect_export_format("-"), ExportFormat::Dotenv);
+    }
+
+    #[test]
+    fn test_export_dotenv() {
+        assert_eq!(
+            export(
+                &[
+                    ("PLAIN", "/usr/bin:/bin"),
+                    ("EMPTY", ""),
+                    ("SPACES", " a # b "),
+                    ("QUOTE", "it's $HOME"),
+                    ("LINES", "a\tb\n\"c\"\\"),
+                ],
+                ExportFormat::Dotenv
+            ),
+            "PLAIN=/usr/bin:/bin\nEMPTY=\nSPACES=' a # b '\nQUOTE=\"it's \\$HOME\"\nLINES=\"a\\tb\\n\\\"c\\\"\\\\\"\n"
+        );
+    }
+
+    #[test]
+    fn test_export_shell() {
+        assert_eq!(
+            export(&[("A", "simple"), ("B", "it's")], ExportFormat::Shell),
+            "export A=simple\nexport B='it'\\''s'\n"
+        );
+    }
+
+    #[test]
+    fn test_export_powershell() {
+        assert_eq!(
+            export(
+                &[("A", "it's"), ("PROGRAMFILES(X86)", "C:\\x")],
+                ExportFormat::Powershell
+            ),
+            "$env:A = 'it''s'\n${env:PROGRAMFILES(X86)} = 'C:\\x'\n"
+        );
+    }
+
+    #[test]
+    fn test_export_docker_env() {
+        assert_eq!(
+            export(&[("A", "a \"b\" $c")], ExportFormat::DockerEnv),
+            "A=a \"b\" $c\n"
+        );
+        let variables = vec![("A".to_string(), "line1\nline2".to_string())];
+        assert!(matches!(
+            serialize_export(&variables, ExportFormat::DockerEnv),
+            Err(ErrorKind::SerializationError(message)) if message.contains("line break")
+        ));
+    }
+
+    #[test]
+    fn test_export_systemd() {
+        assert_eq!(
+            export(
+                &[("A", "plain"), ("B", ""), ("C", "a \"$b\"\nc\\")],
+                ExportFormat::Systemd
+            ),
+            "A=plain\nB=\"\"\nC=\"a \\\"\\$b\\\"\nc\\\\\"\n"
+        );
+    }
+
+    #[test]
+    fn test_export_structured() {
+        let variables = variables();
+        assert_eq!(
+            serialize_export(&variables, ExportFormat::Json).unwrap(),
+            serialize_variables(&variables, OutputFormat::Json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_merge_dotenv() {
+        let content = "# Database\nexport DB_HOST=old # primary\n  DB_PORT = '1' # port\nOTHER=x\n\nDB_HOST=again\n";
+        let variables = vec![
+            ("DB_HOST".to_string(), "new host".to_string()),
+            ("DB_PORT".to_string(), "2".to_string()),
+            ("DB_NAME".to_string(), "app".to_string()),
+        ];
+        assert_eq!(
+            merge_dotenv(content, &variables).unwrap(),
+            "# Database\nexport DB_HOST='new host' # primary\n  DB_PORT=2 # port\nOTHER=x\n\nDB_HOST='new host'\nDB_NAME=app\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_dotenv_line_endings() {
+        let variables = vec![
+            ("A".to_string(), "2".to_string()),
+            ("B".to_string(), "3".to_string()),
+        ];
+        assert_eq!(
+            merge_dotenv("\u{feff}A=1\r\nC=1", &variables).unwrap(),
+            "\u{feff}A=2\r\nC=1\r\nB=3\r\n"
+        );
+        assert_eq!(merge_dotenv("", &variables).unwrap(), "A=2\nB=3\n");
+    }
+
+    #[test]
+    fn test_merge_dotenv_comment_inside_quotes() {
+        let variables = vec![("A".to_string(), "new".to_string())];
+        assert_eq!(
+            merge_dotenv("A=\"x # y\"   # comment\nB=\"\\\"\"\n", &variables).unwrap(),
+            "A=new   # comment\nB=\"\\\"\"\n"
+        );
+        let variables = vec![
+            ("A".to_string(), "x".to_string()),
+            ("B".to_string(), "y".to_string()),
+        ];
+        assert_eq!(
+            merge_dotenv("A= #notcomment\nB=#value # comment\n", &variables).unwrap(),
+            "A=x\nB=y # comment\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_dotenv_invalid_file() {
+        let variables = vec![("A".to_string(), "new".to_string())];
+        assert!(matches!(
+            merge_dotenv("A='unterminated\n", &variables),
+            Err(ErrorKind::ParsingError(_))
+        ));
+    }
+}
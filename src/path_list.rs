@@ -0,0 +1,182 @@
+use std::env;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::models::ErrorKind;
+
+/// Split value of PATH-style variable into entries using platform separator.
+///
+/// Empty entries are skipped
+pub fn split(value: &str) -> Vec<PathBuf> {
+    env::split_paths(value)
+        .filter(|path| !path.as_os_str().is_empty())
+        .collect()
+}
+
+/// Join entries into value of PATH-style variable using platform separator
+pub fn join(entries: &[PathBuf]) -> Result<String, ErrorKind> {
+    env::join_paths(entries)
+        .map(|value| value.to_string_lossy().into_owned())
+        .map_err(|err| ErrorKind::PathListError(err.to_string()))
+}
+
+/// Add paths to the end of list, removing their previous occurrences
+pub fn append(entries: Vec<PathBuf>, paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut result = remove(entries, paths);
+    result.extend(dedupe(paths.to_vec()));
+    result
+}
+
+/// Add paths to the beginning of list, removing their previous occurrences
+pub fn prepend(entries: Vec<PathBuf>, paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut result = dedupe(paths.to_vec());
+    result.extend(remove(entries, paths));
+    result
+}
+
+/// Remove all occurrences of paths from list
+pub fn remove(entries: Vec<PathBuf>, paths: &[PathBuf]) -> Vec<PathBuf> {
+    entries
+        .into_iter()
+        .filter(|entry| !paths.contains(entry))
+        .collect()
+}
+
+/// Remove duplicated entries, keeping first occurrence of each
+pub fn dedupe(entries: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut result: Vec<PathBuf> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !result.contains(&entry) {
+            result.push(entry);
+        }
+    }
+    result
+}
+
+/// Status of entry in list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStatus {
+    /// Entry is existing directory
+    Ok,
+    /// Entry doesn't exist
+    Missing,
+    /// Entry exists, but isn't directory
+    NotDirectory,
+    /// Entry is already present earlier in list
+    Duplicate,
+}
+
+impl Display for PathStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathStatus::Ok => write!(f, "ok"),
+            PathStatus::Missing => write!(f, "missing"),
+            PathStatus::NotDirectory => write!(f, "not a directory"),
+            PathStatus::Duplicate => write!(f, "duplicate"),
+        }
+    }
+}
+
+/// Check that every entry of list is existing directory and isn't duplicated
+pub fn check(entries: &[PathBuf]) -> Vec<(&Path, PathStatus)> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let status = if entries[..index].contains(entry) {
+                PathStatus::Duplicate
+            } else if entry.is_dir() {
+                PathStatus::Ok
+            } else if entry.exists() {
+                PathStatus::NotDirectory
+            } else {
+                PathStatus::Missing
+            };
+            (entry.as_path(), status)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_split_and_join() {
+        let value = env::join_paths(["/usr/bin", "/bin"]).unwrap();
+        let entries = split(value.to_str().unwrap());
+        assert_eq!(entries, paths(&["/usr/bin", "/bin"]));
+        assert_eq!(join(&entries).unwrap(), value.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_split_skips_empty_entries() {
+        assert!(split("").is_empty());
+        let value = env::join_paths(["/bin", "", "/usr/bin"]).unwrap();
+        assert_eq!(split(value.to_str().unwrap()), paths(&["/bin", "/usr/bin"]));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_join_with_separator_in_path() {
+        let result = join(&paths(&["/bin", "/with:colon"]));
+        assert!(matches!(result, Err(ErrorKind::PathListError(_))));
+    }
+
+    #[test]
+    fn test_append() {
+        let entries = paths(&["/a", "/b", "/c"]);
+        assert_eq!(
+            append(entries, &paths(&["/a", "/d"])),
+            paths(&["/b", "/c", "/a", "/d"])
+        );
+    }
+
+    #[test]
+    fn test_prepend() {
+        let entries = paths(&["/a", "/b", "/c"]);
+        assert_eq!(
+            prepend(entries, &paths(&["/c", "/d", "/c"])),
+            paths(&["/c", "/d", "/a", "/b"])
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let entries = paths(&["/a", "/b", "/a", "/c"]);
+        assert_eq!(remove(entries, &paths(&["/a"])), paths(&["/b", "/c"]));
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let entries = paths(&["/a", "/b", "/a", "/c", "/b"]);
+        assert_eq!(dedupe(entries), paths(&["/a", "/b", "/c"]));
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        let missing = dir.path().join("missing");
+        let entries = vec![
+            dir.path().to_path_buf(),
+            file.clone(),
+            missing.clone(),
+            dir.path().to_path_buf(),
+        ];
+        assert_eq!(
+            check(&entries),
+            vec![
+                (dir.path(), PathStatus::Ok),
+                (file.as_path(), PathStatus::NotDirectory),
+                (missing.as_path(), PathStatus::Missing),
+                (dir.path(), PathStatus::Duplicate),
+            ]
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::ErrorKind;
+use crate::utils::write_file_atomically;
+
+/// Name, used in place of snapshot to refer to current environment
+pub const CURRENT: &str = "current";
+
+/// Saved state of environment
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Time of saving, in seconds since Unix epoch
+    pub created: u64,
+    /// Variables with their values
+    pub variables: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    /// Create snapshot of given variables, saved now
+    pub fn new(variables: impl IntoIterator<Item = (String, String)>) -> Self {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Self {
+            created,
+            variables: variables.into_iter().collect(),
+        }
+    }
+}
+
+/// Directory with snapshots, each of them is stored in its own JSON file
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Create store in given directory, which is created on first save
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Save snapshot with given name, existing one is replaced only if `force` is set
+    pub fn save(&self, name: &str, snapshot: &Snapshot, force: bool) -> Result<(), ErrorKind> {
+        let path = self.path(name)?;
+        if !force && path.exists() {
+            return Err(ErrorKind::SnapshotError(format!(
+                "Snapshot {} already exists, use --force to overwrite it",
+                name
+            )));
+        }
+        fs::create_dir_all(&self.dir)
+            .map_err(|err| ErrorKind::FileError(format!("{}: {}", self.dir.display(), err)))?;
+        let content = serde_json::to_string_pretty(snapshot)
+            .map_err(|err| ErrorKind::SerializationError(err.to_string()))?;
+        write_file_atomically(&path, &content, !force)
+    }
+
+    /// Load snapshot with given name
+    pub fn load(&self, name: &str) -> Result<Snapshot, ErrorKind> {
+        let path = self.path(name)?;
+        let content = fs::read_to_string(&path).map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => {
+                ErrorKind::SnapshotError(format!("Snapshot {} doesn't exist", name))
+            }
+            _ => ErrorKind::FileError(format!("{}: {}", path.display(), err)),
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            ErrorKind::SnapshotError(format!("Snapshot {} is invalid: {}", name, err))
+        })
+    }
+
+    /// Get names of saved snapshots in alphabetical order
+    pub fn names(&self) -> Result<Vec<String>, ErrorKind> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(ErrorKind::FileError(format!(
+                    "{}: {}",
+                    self.dir.display(),
+                    err
+                )));
+            }
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                file_name
+                    .strip_suffix(".json")
+                    .filter(|name| validate_name(name).is_ok())
+                    .map(str::to_owned)
+            })
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Get path to file of snapshot, validating its name
+    fn path(&self, name: &str) -> Result<PathBuf, ErrorKind> {
+        validate_name(name)?;
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+/// Check that name of snapshot can be used as file name
+pub fn validate_name(name: &str) -> Result<(), ErrorKind> {
+    if name.is_empty() {
+        return Err(ErrorKind::SnapshotError(
+            "Name of snapshot cannot be empty".to_owned(),
+        ));
+    }
+    if name == CURRENT {
+        return Err(ErrorKind::SnapshotError(format!(
+            "Name {} is reserved for current environment",
+            CURRENT
+        )));
+    }
+    if name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    {
+        return Err(ErrorKind::SnapshotError(format!(
+            "Invalid name of snapshot '{}', only letters, digits, '-', '_' and '.' are allowed",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Format time in seconds since Unix epoch as UTC date and time
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+    // Convert days to civil date, see https://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            created: 0,
+            variables: BTreeMap::from([
+                ("A".to_owned(), "1".to_owned()),
+                ("B".to_owned(), "two words".to_owned()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join("snapshots"));
+        assert!(store.names().unwrap().is_empty());
+
+        store.save("base", &snapshot(), false).unwrap();
+        store.save("after.v2", &snapshot(), false).unwrap();
+        assert_eq!(store.load("base").unwrap(), snapshot());
+        assert_eq!(store.names().unwrap(), vec!["after.v2", "base"]);
+    }
+
+    #[test]
+    fn test_save_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().to_path_buf());
+        store.save("base", &snapshot(), false).unwrap();
+
+        let changed = Snapshot::new(vec![("C".to_owned(), "3".to_owned())]);
+        let result = store.save("base", &changed, false);
+        assert!(matches!(result, Err(ErrorKind::SnapshotError(err)) if err.contains("--force")));
+        assert_eq!(store.load("base").unwrap(), snapshot());
+
+        store.save("base", &changed, true).unwrap();
+        assert_eq!(store.load("base").unwrap(), changed);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().to_path_buf());
+        match store.load("missing") {
+            Err(ErrorKind::SnapshotError(err)) => assert!(err.contains("doesn't exist")),
+            _ => panic!("Expected SnapshotError"),
+        }
+
+        fs::write(dir.path().join("broken.json"), "{").unwrap();
+        match store.load("broken") {
+            Err(ErrorKind::SnapshotError(err)) => assert!(err.contains("is invalid")),
+            _ => panic!("Expected SnapshotError"),
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        for name in ["base", "before-rust_1.80", "A1"] {
+            assert!(validate_name(name).is_ok());
+        }
+        for name in ["", "current", ".hidden", "../escape", "a/b", "with space"] {
+            assert!(validate_name(name).is_err(), "{} is valid", name);
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_timestamp(1792326245), "2026-10-18 12:24:05 UTC");
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use crate::models::SortKey;
+
+/// Sort list of variables by given key.
+///
+/// Ties are broken by variable's name, so result doesn't depend on order given by OS
+pub fn sort_variables(variables: &mut [(String, String)], key: SortKey, reverse: bool) {
+    variables.sort_by(|(a_name, a_value), (b_name, b_value)| {
+        let ordering = match key {
+            SortKey::Name => a_name.cmp(b_name),
+            SortKey::Value => a_value.cmp(b_value).then_with(|| a_name.cmp(b_name)),
+            SortKey::Length => a_value
+                .chars()
+                .count()
+                .cmp(&b_value.chars().count())
+                .then_with(|| a_name.cmp(b_name)),
+        };
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Get prefix of variable's name, e.g. `CARGO_` for `CARGO_HOME`
+fn name_prefix(name: &str) -> Option<&str> {
+    match name.find('_') {
+        Some(index) if index > 0 => Some(&name[..=index]),
+        _ => None,
+    }
+}
+
+/// Group of variables sharing same prefix
+pub type VariablesGroup = (Option<String>, Vec<(String, String)>);
+
+/// Split list of variables into groups by prefix of their names.
+///
+/// Variables without prefix or with prefix unique to them are placed in first group
+/// without prefix, other groups are ordered by prefix. Order of variables
+/// inside of groups is kept.
+pub fn group_by_prefix(variables: Vec<(String, String)>) -> Vec<VariablesGroup> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for (name, _) in &variables {
+        if let Some(prefix) = name_prefix(name) {
+            *counts.entry(prefix).or_default() += 1;
+        }
+    }
+    let shared: Vec<String> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(prefix, _)| prefix.to_owned())
+        .collect();
+
+    let mut ungrouped = vec![];
+    let mut groups: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    for (name, value) in variables {
+        match name_prefix(&name).filter(|prefix| shared.iter().any(|p| p == prefix)) {
+            Some(prefix) => groups
+                .entry(prefix.to_owned())
+                .or_default()
+                .push((name, value)),
+            None => ungrouped.push((name, value)),
+        }
+    }
+
+    let mut result = vec![];
+    if !ungrouped.is_empty() {
+        result.push((None, ungrouped));
+    }
+    result.extend(
+        groups
+            .into_iter()
+            .map(|(prefix, group)| (Some(prefix), group)),
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> Vec<(String, String)> {
+        vec![
+            (
+                "XDG_DATA_HOME".to_string(),
+                "/home/user/.local/share".to_string(),
+            ),
+            ("CARGO_HOME".to_string(), "/home/user/.cargo".to_string()),
+            ("HOME".to_string(), "/home/user".to_string()),
+            (
+                "XDG_CONFIG_HOME".to_string(),
+                "/home/user/.config".to_string(),
+            ),
+            ("CARGO_TERM_COLOR".to_string(), "always".to_string()),
+            ("LANG_ONLY".to_string(), "en".to_string()),
+        ]
+    }
+
+    fn names(variables: &[(String, String)]) -> Vec<&str> {
+        variables.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_name() {
+        let mut list = variables();
+        sort_variables(&mut list, SortKey::Name, false);
+        assert_eq!(
+            names(&list),
+            vec![
+                "CARGO_HOME",
+                "CARGO_TERM_COLOR",
+                "HOME",
+                "LANG_ONLY",
+                "XDG_CONFIG_HOME",
+                "XDG_DATA_HOME"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_name_reversed() {
+        let mut list = variables();
+        sort_variables(&mut list, SortKey::Name, true);
+        assert_eq!(names(&list)[0], "XDG_DATA_HOME");
+        assert_eq!(names(&list)[5], "CARGO_HOME");
+    }
+
+    #[test]
+    fn test_sort_by_value() {
+        let mut list = variables();
+        sort_variables(&mut list, SortKey::Value, false);
+        assert_eq!(
+            names(&list),
+            vec![
+                "HOME",
+                "CARGO_HOME",
+                "XDG_CONFIG_HOME",
+                "XDG_DATA_HOME",
+                "CARGO_TERM_COLOR",
+                "LANG_ONLY"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_length_ties_broken_by_name() {
+        let mut list = vec![
+            ("B".to_string(), "xx".to_string()),
+            ("A".to_string(), "yy".to_string()),
+            ("C".to_string(), "z".to_string()),
+        ];
+        sort_variables(&mut list, SortKey::Length, false);
+        assert_eq!(names(&list), vec!["C", "A", "B"]);
+    }
+
+    #[test]
+    fn test_name_prefix() {
+        assert_eq!(name_prefix("CARGO_HOME"), Some("CARGO_"));
+        assert_eq!(name_prefix("HOME"), None);
+        assert_eq!(name_prefix("_HIDDEN"), None);
+    }
+
+    #[test]
+    fn test_group_by_prefix() {
+        let groups = group_by_prefix(variables());
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].0, None);
+        assert_eq!(names(&groups[0].1), vec!["HOME", "LANG_ONLY"]);
+        assert_eq!(groups[1].0, Some("CARGO_".to_string()));
+        assert_eq!(names(&groups[1].1), vec!["CARGO_HOME", "CARGO_TERM_COLOR"]);
+        assert_eq!(groups[2].0, Some("XDG_".to_string()));
+        assert_eq!(
+            names(&groups[2].1),
+            vec!["XDG_DATA_HOME", "XDG_CONFIG_HOME"]
+        );
+    }
+
+    #[test]
+    fn test_group_by_prefix_empty() {
+        assert!(group_by_prefix(vec![]).is_empty());
+    }
+}
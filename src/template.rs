@@ -0,0 +1,300 @@
+use crate::models::ErrorKind;
+
+/// Field of variable, that can be used in template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Variable's name
+    Name,
+    /// Variable's value
+    Value,
+    /// Position of variable in list, starting from 0
+    Index,
+    /// Length of variable's value in characters
+    Len,
+}
+
+/// Filter, applied to field's value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    /// Quote for POSIX shell
+    Shell,
+    /// Quote as JSON string
+    Json,
+    /// Convert to uppercase
+    Upper,
+    /// Convert to lowercase
+    Lower,
+    /// Keep only given number of characters
+    Truncate(usize),
+}
+
+/// Part of parsed template
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder { field: Field, filters: Vec<Filter> },
+}
+
+/// Template for printing variables.
+///
+/// Placeholders are written in braces, e.g. `{name}` or `{value|shell}`, and filters
+/// can be chained: `{value|truncate:40|json}`. Literal braces are written as `{{` and `}}`.
+/// Template is parsed once and values are substituted in single pass, so values
+/// containing placeholders are printed as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse template
+    pub fn parse(source: &str) -> Result<Self, ErrorKind> {
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut chars = source.char_indices().peekable();
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|(_, c)| *c == '{').is_some() => literal.push('{'),
+                '}' if chars.next_if(|(_, c)| *c == '}').is_some() => literal.push('}'),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => placeholder.push(c),
+                            None => {
+                                return Err(template_error(format!(
+                                    "unclosed placeholder at position {}",
+                                    position
+                                )));
+                            }
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(parse_placeholder(&placeholder, position)?);
+                }
+                '}' => {
+                    return Err(template_error(format!(
+                        "unmatched '}}' at position {}, use '}}}}' to print it",
+                        position
+                    )));
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Render template for given variable
+    pub fn render(&self, name: &str, value: &str, index: usize) -> String {
+        let mut result = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => result.push_str(literal),
+                Segment::Placeholder { field, filters } => {
+                    let mut text = match field {
+                        Field::Name => name.to_owned(),
+                        Field::Value => value.to_owned(),
+                        Field::Index => index.to_string(),
+                        Field::Len => value.chars().count().to_string(),
+                    };
+                    for filter in filters {
+                        text = apply_filter(*filter, &text);
+                    }
+                    result.push_str(&text);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Create error for invalid template
+fn template_error(message: String) -> ErrorKind {
+    ErrorKind::ParsingError(format!("invalid template: {}", message))
+}
+
+/// Parse content of placeholder, e.g. `value|truncate:40`
+fn parse_placeholder(placeholder: &str, position: usize) -> Result<Segment, ErrorKind> {
+    let mut parts = placeholder.split('|').map(str::trim);
+    let field = match parts.next().unwrap_or_default() {
+        "name" => Field::Name,
+        "value" => Field::Value,
+        "index" => Field::Index,
+        "len" => Field::Len,
+        other => {
+            return Err(template_error(format!(
+                "unknown placeholder '{}' at position {}",
+                other, position
+            )));
+        }
+    };
+    let filters = parts
+        .map(|filter| parse_filter(filter, position))
+        .collect::<Result<_, _>>()?;
+    Ok(Segment::Placeholder { field, filters })
+}
+
+/// Parse filter with optional argument, e.g. `truncate:40`
+fn parse_filter(filter: &str, position: usize) -> Result<Filter, ErrorKind> {
+    let (name, argument) = match filter.split_once(':') {
+        Some((name, argument)) => (name.trim(), Some(argument.trim())),
+        None => (filter, None),
+    };
+    match (name, argument) {
+        ("shell", None) => Ok(Filter::Shell),
+        ("json", None) => Ok(Filter::Json),
+        ("upper", None) => Ok(Filter::Upper),
+        ("lower", None) => Ok(Filter::Lower),
+        ("truncate", Some(length)) => length.parse().map(Filter::Truncate).map_err(|_| {
+            template_error(format!(
+                "invalid length '{}' for filter 'truncate' at position {}",
+                length, position
+            ))
+        }),
+        ("truncate", None) => Err(template_error(format!(
+            "filter 'truncate' requires length, e.g. 'truncate:40', at position {}",
+            position
+        ))),
+        ("shell" | "json" | "upper" | "lower", Some(_)) => Err(template_error(format!(
+            "filter '{}' doesn't take arguments, at position {}",
+            name, position
+        ))),
+        _ => Err(template_error(format!(
+            "unknown filter '{}' at position {}",
+            name, position
+        ))),
+    }
+}
+
+/// Apply filter to text
+fn apply_filter(filter: Filter, text: &str) -> String {
+    match filter {
+        Filter::Shell => shell_quote(text),
+        Filter::Json => serde_json::to_string(text).expect("String should be serializable"),
+        Filter::Upper => text.to_uppercase(),
+        Filter::Lower => text.to_lowercase(),
+        Filter::Truncate(length) => text.chars().take(length).collect(),
+    }
+}
+
+/// Quote string for POSIX shell, leaving it as is if it is safe
+pub fn shell_quote(text: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-@%+=:,./".contains(c);
+    if !text.is_empty() && text.chars().all(is_safe) {
+        text.to_owned()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str, name: &str, value: &str) -> String {
+        Template::parse(template).unwrap().render(name, value, 0)
+    }
+
+    #[test]
+    fn test_render_simple() {
+        assert_eq!(
+            render("{name} = \"{value}\"", "VAR", "value"),
+            "VAR = \"value\""
+        );
+    }
+
+    #[test]
+    fn test_render_without_placeholders() {
+        assert_eq!(render("text", "VAR", "value"), "text");
+        assert_eq!(render("", "VAR", "value"), "");
+    }
+
+    #[test]
+    fn test_render_single_pass() {
+        // Placeholders inside of values aren't substituted
+        assert_eq!(render("{value}", "VAR", "{name}"), "{name}");
+        assert_eq!(render("{name}={value}", "{value}", "x"), "{value}=x");
+    }
+
+    #[test]
+    fn test_render_escaped_braces() {
+        assert_eq!(render("{{{name}}}", "VAR", "value"), "{VAR}");
+        assert_eq!(render("{{name}}", "VAR", "value"), "{name}");
+    }
+
+    #[test]
+    fn test_render_index_and_len() {
+        let template = Template::parse("{index}: {name} ({len})").unwrap();
+        assert_eq!(template.render("VAR", "värde", 3), "3: VAR (5)");
+    }
+
+    #[test]
+    fn test_filter_shell() {
+        assert_eq!(render("{value|shell}", "VAR", "simple"), "simple");
+        assert_eq!(render("{value|shell}", "VAR", "it's"), "'it'\\''s'");
+        assert_eq!(render("{value|shell}", "VAR", "a b"), "'a b'");
+        assert_eq!(render("{value|shell}", "VAR", ""), "''");
+        assert_eq!(render("{value|shell}", "VAR", "$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn test_filter_json() {
+        assert_eq!(
+            render("{value|json}", "VAR", "say \"hi\"\n"),
+            "\"say \\\"hi\\\"\\n\""
+        );
+    }
+
+    #[test]
+    fn test_filter_case() {
+        assert_eq!(
+            render("{name|lower}={value|upper}", "VAR", "value"),
+            "var=VALUE"
+        );
+    }
+
+    #[test]
+    fn test_filter_truncate() {
+        assert_eq!(render("{value|truncate:3}", "VAR", "abcdef"), "abc");
+        assert_eq!(render("{value|truncate:10}", "VAR", "abc"), "abc");
+    }
+
+    #[test]
+    fn test_filter_chain() {
+        assert_eq!(
+            render(
+                "{ value | truncate:5 | upper | json }",
+                "VAR",
+                "hello world"
+            ),
+            "\"HELLO\""
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = vec![
+            ("{unknown}", "unknown placeholder 'unknown' at position 0"),
+            ("x{value|reverse}", "unknown filter 'reverse' at position 1"),
+            ("{value|truncate}", "filter 'truncate' requires length"),
+            ("{value|truncate:abc}", "invalid length 'abc'"),
+            ("{value|upper:1}", "filter 'upper' doesn't take arguments"),
+            ("{value", "unclosed placeholder at position 0"),
+            ("value}", "unmatched '}' at position 5"),
+        ];
+        for (template, expected) in cases {
+            match Template::parse(template) {
+                Err(ErrorKind::ParsingError(err)) => {
+                    assert!(err.contains(expected), "{}: {}", template, err)
+                }
+                _ => panic!("Expected ParsingError for {}", template),
+            }
+        }
+    }
+}
@@ -1,53 +1,279 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
 #[cfg(test)]
 use std::process::Stdio;
-use std::process::{Command, ExitStatus};
+use std::process::{Command, ExitCode, ExitStatus};
+#[cfg(all(unix, not(test)))]
+use std::sync::atomic::{AtomicI32, Ordering};
 
-use crate::models::ErrorKind;
+use crate::models::{ErrorKind, NameValidation, RunArgs};
 use log::{error, info};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use tempfile::NamedTempFile;
 
-/// Runs given command using system shell
-pub fn run(process: String) -> Result<ExitStatus, ErrorKind> {
-    if process.is_empty() {
-        error!("got 0 arguments as command name");
-        return Err(ErrorKind::StartingProcessError);
+/// Changes of environment, made by command for child process
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChildEnv {
+    /// Variables set by command
+    pub vars: Vec<(String, String)>,
+    /// Names of variables deleted by command
+    pub removed: Vec<String>,
+}
+
+impl ChildEnv {
+    /// Create environment with given variables set
+    pub fn with_vars(vars: Vec<(String, String)>) -> Self {
+        Self {
+            vars,
+            ..Default::default()
+        }
     }
 
-    // Use platform-specific shell commands
-    #[cfg(windows)]
-    let (shell, shell_arg) = ("cmd", "/C");
-    #[cfg(not(windows))]
-    let (shell, shell_arg) = ("sh", "-c");
+    /// Build full environment of child process.
+    ///
+    /// It's based on current environment, or only on kept variables from it if
+    /// `clear_env` is set, with changes made by command applied on top
+    pub fn build(&self, options: &RunArgs) -> BTreeMap<OsString, OsString> {
+        let mut result: BTreeMap<OsString, OsString> = env::vars_os()
+            .filter(|(name, _)| {
+                let name = name.to_string_lossy();
+                !options.clear_env
+                    || options.keep.iter().any(|keep| *keep == name)
+                    || options
+                        .keep_prefix
+                        .iter()
+                        .any(|prefix| name.starts_with(prefix.as_str()))
+            })
+            .collect();
+        for name in &self.removed {
+            result.remove(OsStr::new(name));
+        }
+        for (name, value) in &self.vars {
+            result.insert(name.into(), value.into());
+        }
+        result
+    }
+}
+
+/// Runs given process with explicitly built environment.
+///
+/// By default arguments are joined with spaces and passed to system shell,
+/// with `no_shell` they are passed to program as is
+pub fn run(process: &[String], options: &RunArgs, env: &ChildEnv) -> Result<ExitStatus, ErrorKind> {
+    let mut cmd = if options.no_shell {
+        match process.split_first() {
+            Some((program, args)) if !program.is_empty() => {
+                let mut cmd = Command::new(program);
+                cmd.args(args);
+                cmd
+            }
+            _ => {
+                error!("got 0 arguments as command name");
+                return Err(ErrorKind::StartingProcessError);
+            }
+        }
+    } else {
+        let process = process.join(" ");
+        if process.is_empty() {
+            error!("got 0 arguments as command name");
+            return Err(ErrorKind::StartingProcessError);
+        }
+
+        // Use platform-specific shell commands
+        #[cfg(windows)]
+        let (shell, shell_arg) = ("cmd", "/C");
+        #[cfg(not(windows))]
+        let (shell, shell_arg) = ("sh", "-c");
+
+        let mut cmd = Command::new(shell);
+        cmd.arg(shell_arg).arg(process);
+        cmd
+    };
+
+    cmd.env_clear().envs(env.build(options));
 
-    let mut cmd = Command::new(shell);
-    cmd.arg(shell_arg).arg(process);
+    if options.exec {
+        return exec(cmd);
+    }
 
     #[cfg(test)]
     cmd.stderr(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null());
-    match cmd.status() {
-        Ok(code) => {
-            info!(
-                "process exited with exit code {}",
-                code.code().unwrap_or_default()
-            );
-            Ok(code)
-        }
+    // Child process should decide how to handle interruption, so envfetch doesn't exit
+    // on signals and forwards them to it. Handlers are installed before spawning to not miss them.
+    // They are process-wide, so they aren't used in tests, which run processes in parallel
+    #[cfg(all(unix, not(test)))]
+    let forwarding = SignalForwarding::new();
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
         Err(err) => {
             error!("can't start process: {}", err);
+            return Err(ErrorKind::StartingProcessError);
+        }
+    };
+    #[cfg(all(unix, not(test)))]
+    forwarding.set_child(child.id());
+    match child.wait() {
+        Ok(status) => {
+            info!("process exited with {}", status);
+            Ok(status)
+        }
+        Err(err) => {
+            error!("can't wait for process: {}", err);
             Err(ErrorKind::StartingProcessError)
         }
     }
 }
 
-/// Validate variable name
-pub fn validate_var_name(name: &str) -> Result<(), String> {
+/// Convert exit status of process to exit code of envfetch.
+///
+/// If process was terminated by signal, conventional `128 + signal` code is used
+pub fn exit_code(status: ExitStatus) -> ExitCode {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return ExitCode::from(128u8.wrapping_add(signal as u8));
+    }
+    // Codes that don't fit into byte are reported as generic failure
+    match status.code().map(u8::try_from) {
+        Some(Ok(code)) => ExitCode::from(code),
+        _ => ExitCode::FAILURE,
+    }
+}
+
+/// Process ID of running child process, which receives forwarded signals
+#[cfg(all(unix, not(test)))]
+static CHILD_PID: AtomicI32 = AtomicI32::new(0);
+
+/// Signal received before child process was started, it's delivered after spawning
+#[cfg(all(unix, not(test)))]
+static PENDING_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// Signal handler, sending received signal to child process.
+///
+/// Signals sent by terminal (without sender's PID) already reach running child, as it's
+/// in the same foreground process group, so they aren't sent again. Whoever takes pending
+/// signal delivers it, so it's sent exactly once even if it arrives while child is being registered
+#[cfg(all(unix, not(test)))]
+extern "C" fn forward_signal(
+    signal: libc::c_int,
+    info: *mut libc::siginfo_t,
+    _context: *mut libc::c_void,
+) {
+    let pid = CHILD_PID.load(Ordering::SeqCst);
+    let from_terminal = unsafe { (*info).si_pid() } == 0;
+    if pid > 0 && from_terminal {
+        return;
+    }
+    PENDING_SIGNAL.store(signal, Ordering::SeqCst);
+    if pid > 0 {
+        let pending = PENDING_SIGNAL.swap(0, Ordering::SeqCst);
+        if pending != 0 {
+            unsafe { libc::kill(pid, pending) };
+        }
+    }
+}
+
+/// Forwards SIGINT and SIGTERM to child process until dropped
+#[cfg(all(unix, not(test)))]
+struct SignalForwarding {
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+}
+
+#[cfg(all(unix, not(test)))]
+impl SignalForwarding {
+    fn new() -> Self {
+        let previous = [libc::SIGINT, libc::SIGTERM]
+            .iter()
+            .map(|&signal| unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = forward_signal
+                    as extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void)
+                    as libc::sighandler_t;
+                action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+                libc::sigemptyset(&mut action.sa_mask);
+                let mut previous: libc::sigaction = std::mem::zeroed();
+                libc::sigaction(signal, &action, &mut previous);
+                (signal, previous)
+            })
+            .collect();
+        Self { previous }
+    }
+
+    /// Set process, which receives forwarded signals, and deliver signal received before
+    fn set_child(&self, pid: u32) {
+        CHILD_PID.store(pid as i32, Ordering::SeqCst);
+        let pending = PENDING_SIGNAL.swap(0, Ordering::SeqCst);
+        if pending != 0 {
+            unsafe { libc::kill(pid as i32, pending) };
+        }
+    }
+}
+
+#[cfg(all(unix, not(test)))]
+impl Drop for SignalForwarding {
+    fn drop(&mut self) {
+        for (signal, previous) in &self.previous {
+            unsafe { libc::sigaction(*signal, previous, std::ptr::null_mut()) };
+        }
+        CHILD_PID.store(0, Ordering::SeqCst);
+        // Signal wasn't delivered, as child wasn't started, so it's handled by envfetch itself
+        let pending = PENDING_SIGNAL.swap(0, Ordering::SeqCst);
+        if pending != 0 {
+            unsafe { libc::raise(pending) };
+        }
+    }
+}
+
+/// Replace current process with given command, returns only if it can't be done
+#[cfg(unix)]
+fn exec(mut cmd: Command) -> Result<ExitStatus, ErrorKind> {
+    use std::os::unix::process::CommandExt;
+
+    let err = cmd.exec();
+    error!("can't start process: {}", err);
+    Err(ErrorKind::StartingProcessError)
+}
+
+/// Replacing current process isn't supported on this platform
+#[cfg(not(unix))]
+fn exec(_cmd: Command) -> Result<ExitStatus, ErrorKind> {
+    error!("replacing current process is supported only on Unix");
+    Err(ErrorKind::StartingProcessError)
+}
+
+/// Validate variable name using given rules
+pub fn validate_var_name(name: &str, rules: NameValidation) -> Result<(), String> {
     if name.is_empty() {
         return Err("Variable name cannot be empty".to_string());
     }
-    if name.contains(' ') {
-        return Err("Variable name cannot contain spaces".to_string());
+    for (index, c) in name.chars().enumerate() {
+        let valid = match rules {
+            // OS doesn't allow '=' and NUL, whitespace and control characters break dotenv files
+            NameValidation::Permissive => c != '=' && !c.is_whitespace() && !c.is_control(),
+            NameValidation::Strict => {
+                c == '_' || c.is_ascii_alphabetic() || (index > 0 && c.is_ascii_digit())
+            }
+        };
+        if !valid {
+            let hint = match rules {
+                NameValidation::Permissive => "",
+                NameValidation::Strict => {
+                    ", only letters, digits and underscores are allowed and name can't start with digit"
+                }
+            };
+            return Err(format!(
+                "Invalid character '{}' at position {} in variable name '{}'{}",
+                c.escape_debug(),
+                index + 1,
+                name.escape_debug(),
+                hint
+            ));
+        }
     }
     Ok(())
 }
@@ -64,6 +290,41 @@ pub fn find_similar_string(string: String, strings: Vec<String>, threshold: f64)
         .collect::<Vec<_>>()
 }
 
+/// Write file through temporary file in same directory, which is then renamed.
+///
+/// New files are accessible only by owner, while existing ones keep their permissions.
+/// With `no_clobber` existing file is never overwritten
+pub fn write_file_atomically(
+    path: &Path,
+    content: &str,
+    no_clobber: bool,
+) -> Result<(), ErrorKind> {
+    let file_error =
+        |err: std::io::Error| ErrorKind::FileError(format!("{}: {}", path.display(), err));
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut file = NamedTempFile::new_in(directory).map_err(file_error)?;
+    file.write_all(content.as_bytes()).map_err(file_error)?;
+    file.as_file().sync_all().map_err(file_error)?;
+    if no_clobber {
+        file.persist_noclobber(path)
+            .map_err(|err| match err.error.kind() {
+                std::io::ErrorKind::AlreadyExists => {
+                    ErrorKind::FileError(format!("{} already exists", path.display()))
+                }
+                _ => file_error(err.error),
+            })?;
+    } else {
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(file.path(), metadata.permissions()).map_err(file_error)?;
+        }
+        file.persist(path).map_err(|err| file_error(err.error))?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,7 +341,8 @@ mod tests {
         ];
 
         for name in valid_names {
-            assert!(validate_var_name(name).is_ok());
+            assert!(validate_var_name(name, NameValidation::Permissive).is_ok());
+            assert!(validate_var_name(name, NameValidation::Strict).is_ok());
         }
     }
 
@@ -95,15 +357,69 @@ mod tests {
         ];
 
         for name in invalid_names {
-            let result = validate_var_name(name);
+            let result = validate_var_name(name, NameValidation::Permissive);
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), "Variable name cannot contain spaces");
+            let position = name.find(' ').unwrap() + 1;
+            assert_eq!(
+                result.unwrap_err(),
+                format!(
+                    "Invalid character ' ' at position {} in variable name '{}'",
+                    position, name
+                )
+            );
         }
     }
 
+    #[test]
+    fn test_validate_var_name_forbidden_characters() {
+        let cases = vec![
+            (
+                "A=B",
+                "Invalid character '=' at position 2 in variable name 'A=B'",
+            ),
+            (
+                "A\0B",
+                "Invalid character '\\0' at position 2 in variable name 'A\\0B'",
+            ),
+            (
+                "LINE\nBREAK",
+                "Invalid character '\\n' at position 5 in variable name 'LINE\\nBREAK'",
+            ),
+            (
+                "TAB\t",
+                "Invalid character '\\t' at position 4 in variable name 'TAB\\t'",
+            ),
+        ];
+        for (name, message) in cases {
+            assert_eq!(
+                validate_var_name(name, NameValidation::Permissive).unwrap_err(),
+                message
+            );
+            assert!(validate_var_name(name, NameValidation::Strict).is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_var_name_strict() {
+        for name in ["1A", "my-var", "ÄPFEL", "a.b"] {
+            assert!(validate_var_name(name, NameValidation::Permissive).is_ok());
+            assert!(validate_var_name(name, NameValidation::Strict).is_err());
+        }
+        assert_eq!(
+            validate_var_name("my-var", NameValidation::Strict).unwrap_err(),
+            "Invalid character '-' at position 3 in variable name 'my-var', only letters, \
+             digits and underscores are allowed and name can't start with digit"
+        );
+        assert!(
+            validate_var_name("1A", NameValidation::Strict)
+                .unwrap_err()
+                .starts_with("Invalid character '1' at position 1")
+        );
+    }
+
     #[test]
     fn test_validate_var_name_empty() {
-        let result = validate_var_name("");
+        let result = validate_var_name("", NameValidation::Strict);
         assert!(
             result.is_err(),
             "Empty string should be invalid as per current implementation"
@@ -154,13 +470,21 @@ mod tests {
         #[cfg(not(windows))]
         let cmd = "echo test";
 
-        let result = run(cmd.to_string());
+        let result = run(
+            &[cmd.to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_run_nonexistent_command() {
-        let result = run("nonexistent_command_123".to_string());
+        let result = run(
+            &["nonexistent_command_123".to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_ok());
         assert!(!result.unwrap().success());
     }
@@ -172,14 +496,18 @@ mod tests {
         #[cfg(not(windows))]
         let cmd = "false";
 
-        let result = run(cmd.to_string());
+        let result = run(
+            &[cmd.to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_ok());
         assert!(!result.unwrap().success());
     }
 
     #[test]
     fn test_run_empty_command() {
-        let result = run("".to_string());
+        let result = run(&["".to_string()], &RunArgs::default(), &ChildEnv::default());
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
@@ -190,7 +518,11 @@ mod tests {
     #[test]
     fn test_run_invalid_executable() {
         // Test with a command that should fail to execute
-        let result = run("\0invalid".to_string());
+        let result = run(
+            &["\0invalid".to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
@@ -201,7 +533,11 @@ mod tests {
     #[test]
     fn test_run_null_command() {
         // Test with a null character in command which should fail to start
-        let result = run("echo \0test".to_string());
+        let result = run(
+            &["echo \0test".to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
@@ -213,10 +549,131 @@ mod tests {
     fn test_run_with_very_long_command() {
         // Create a command that's too long to execute
         let very_long_command = "x".repeat(65536);
-        let _ = run(very_long_command);
+        let _ = run(
+            &[very_long_command],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         // Different OS's may return different error types for too-long commands
     }
 
+    #[test]
+    #[cfg(not(windows))]
+    fn test_run_without_shell() {
+        let options = RunArgs {
+            no_shell: true,
+            ..Default::default()
+        };
+        // Argument with spaces and quotes is passed as is
+        let process = vec!["test".to_string(), "a 'b' \"c\"".to_string()];
+        assert!(
+            run(&process, &options, &ChildEnv::default())
+                .unwrap()
+                .success()
+        );
+        // Shell syntax isn't interpreted
+        let process = vec!["true;".to_string()];
+        assert!(run(&process, &options, &ChildEnv::default()).is_err());
+    }
+
+    #[test]
+    fn test_run_without_shell_empty_command() {
+        let options = RunArgs {
+            no_shell: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            run(&[], &options, &ChildEnv::default()),
+            Err(ErrorKind::StartingProcessError)
+        ));
+        assert!(matches!(
+            run(&["".to_string()], &options, &ChildEnv::default()),
+            Err(ErrorKind::StartingProcessError)
+        ));
+    }
+
+    fn env_var(env: &BTreeMap<OsString, OsString>, name: &str) -> Option<String> {
+        env.get(OsStr::new(name))
+            .map(|value| value.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn test_child_env_inherits_current_env() {
+        unsafe { env::set_var("TEST_CHILD_ENV_INHERITED", "value") };
+        unsafe { env::set_var("TEST_CHILD_ENV_REMOVED", "value") };
+        let child_env = ChildEnv {
+            vars: vec![("TEST_CHILD_ENV_SET".to_string(), "new".to_string())],
+            removed: vec!["TEST_CHILD_ENV_REMOVED".to_string()],
+        };
+        let result = child_env.build(&RunArgs::default());
+        assert_eq!(
+            env_var(&result, "TEST_CHILD_ENV_INHERITED"),
+            Some("value".to_string())
+        );
+        assert_eq!(
+            env_var(&result, "TEST_CHILD_ENV_SET"),
+            Some("new".to_string())
+        );
+        assert_eq!(env_var(&result, "TEST_CHILD_ENV_REMOVED"), None);
+        unsafe { env::remove_var("TEST_CHILD_ENV_INHERITED") };
+        unsafe { env::remove_var("TEST_CHILD_ENV_REMOVED") };
+    }
+
+    #[test]
+    fn test_child_env_cleared() {
+        unsafe { env::set_var("TEST_CLEAR_ENV_KEPT", "1") };
+        unsafe { env::set_var("TEST_CLEAR_ENV_PREFIX_A", "2") };
+        unsafe { env::set_var("TEST_CLEAR_ENV_DROPPED", "3") };
+        let options = RunArgs {
+            clear_env: true,
+            keep: vec!["TEST_CLEAR_ENV_KEPT".to_string()],
+            keep_prefix: vec!["TEST_CLEAR_ENV_PREFIX_".to_string()],
+            ..Default::default()
+        };
+        let child_env =
+            ChildEnv::with_vars(vec![("TEST_CLEAR_ENV_SET".to_string(), "4".to_string())]);
+        let result = child_env.build(&options);
+        let mut names: Vec<String> = result
+            .keys()
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        names.retain(|name| name.starts_with("TEST_") || name == "HOME");
+        assert_eq!(
+            names,
+            vec![
+                "TEST_CLEAR_ENV_KEPT",
+                "TEST_CLEAR_ENV_PREFIX_A",
+                "TEST_CLEAR_ENV_SET"
+            ]
+        );
+        unsafe { env::remove_var("TEST_CLEAR_ENV_KEPT") };
+        unsafe { env::remove_var("TEST_CLEAR_ENV_PREFIX_A") };
+        unsafe { env::remove_var("TEST_CLEAR_ENV_DROPPED") };
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_exit_code() {
+        use std::os::unix::process::ExitStatusExt;
+
+        assert_eq!(exit_code(ExitStatus::from_raw(0)), ExitCode::SUCCESS);
+        assert_eq!(exit_code(ExitStatus::from_raw(3 << 8)), ExitCode::from(3));
+        // Terminated by SIGKILL
+        assert_eq!(exit_code(ExitStatus::from_raw(9)), ExitCode::from(137));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_killed_by_signal() {
+        let result = run(
+            &["kill -TERM $$".to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        )
+        .unwrap();
+        assert_eq!(exit_code(result), ExitCode::from(143));
+    }
+
     #[test]
     fn test_run() {
         #[cfg(windows)]
@@ -224,7 +681,11 @@ mod tests {
         #[cfg(not(windows))]
         let cmd = "echo test";
 
-        let result = run(cmd.to_string());
+        let result = run(
+            &[cmd.to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_ok());
     }
 
@@ -235,7 +696,55 @@ mod tests {
         #[cfg(not(windows))]
         let cmd = "false";
 
-        let result = run(cmd.to_string());
+        let result = run(
+            &[cmd.to_string()],
+            &RunArgs::default(),
+            &ChildEnv::default(),
+        );
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_write_file_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.env");
+        write_file_atomically(&path, "A=1\n", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A=1\n");
+        write_file_atomically(&path, "A=2\n", false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A=2\n");
+        // Temporary file is renamed, so only target is left
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_file_atomically_no_clobber() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.env");
+        write_file_atomically(&path, "A=1\n", true).unwrap();
+        let result = write_file_atomically(&path, "A=2\n", true);
+        assert!(
+            matches!(result, Err(ErrorKind::FileError(err)) if err.ends_with("already exists"))
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "A=1\n");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_file_atomically_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("new.env");
+        write_file_atomically(&path, "A=1\n", false).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let path = dir.path().join("existing.env");
+        fs::write(&path, "A=1\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        write_file_atomically(&path, "A=2\n", false).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
 }
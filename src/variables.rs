@@ -1,14 +1,15 @@
 use std::{env, io::Write};
 
 use crate::models::ErrorKind;
+use crate::template::Template;
 
 /// List of variables
 type VariablesList = Vec<(String, String)>;
 
-/// Print all environment variables
-pub fn print_env<W: Write>(format: &str, mut buffer: W) {
-    for (key, value) in get_variables() {
-        let entry = format.replace("{name}", &key).replace("{value}", &value);
+/// Print given environment variables using template
+pub fn print_env<W: Write>(variables: &[(String, String)], template: &Template, mut buffer: W) {
+    for (index, (key, value)) in variables.iter().enumerate() {
+        let entry = template.render(key, value, index);
         writeln!(buffer, "{}", entry).expect("Failed to write to buffer");
     }
 }
@@ -47,6 +48,10 @@ mod tests {
     use super::*;
     use std::env;
 
+    fn default_template() -> Template {
+        Template::parse("{name} = \"{value}\"").unwrap()
+    }
+
     #[test]
     fn test_get_variables_list() {
         unsafe { env::set_var("TEST_GET_VARIABLES", "test_value") };
@@ -67,7 +72,7 @@ mod tests {
     fn test_print_env() {
         unsafe { env::set_var("TEST_PRINT_VAR", "test_value") };
         let mut buffer = vec![];
-        print_env("{name} = \"{value}\"", &mut buffer);
+        print_env(&get_variables(), &default_template(), &mut buffer);
         assert!(
             String::from_utf8(buffer)
                 .unwrap()
@@ -76,6 +81,32 @@ mod tests {
         unsafe { env::remove_var("TEST_PRINT_VAR") };
     }
 
+    #[test]
+    fn test_print_env_value_with_placeholder() {
+        let variables = vec![("TEST_PLACEHOLDER".to_string(), "{name}".to_string())];
+        let mut buffer = vec![];
+        print_env(&variables, &default_template(), &mut buffer);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "TEST_PLACEHOLDER = \"{name}\"\n"
+        );
+    }
+
+    #[test]
+    fn test_print_env_index() {
+        let variables = vec![
+            ("A".to_string(), "1".to_string()),
+            ("B".to_string(), "2".to_string()),
+        ];
+        let mut buffer = vec![];
+        print_env(
+            &variables,
+            &Template::parse("{index}:{name}").unwrap(),
+            &mut buffer,
+        );
+        assert_eq!(String::from_utf8(buffer).unwrap(), "0:A\n1:B\n");
+    }
+
     #[test]
     fn test_delete_variable() {
         unsafe { env::set_var("TEST_DELETE_VAR", "test_value") };
@@ -99,7 +130,7 @@ mod tests {
         unsafe { env::set_var("TEST_VAR_2", "value2") };
 
         let mut buffer = vec![];
-        print_env("{name} = \"{value}\"", &mut buffer);
+        print_env(&get_variables(), &default_template(), &mut buffer);
         assert!(
             String::from_utf8(buffer.clone())
                 .unwrap()
@@ -121,7 +152,7 @@ mod tests {
         unsafe { env::set_var("TEST_EMPTY", "") };
 
         let mut buffer = vec![];
-        print_env("{name} = \"{value}\"", &mut buffer);
+        print_env(&get_variables(), &default_template(), &mut buffer);
         assert!(
             String::from_utf8(buffer)
                 .unwrap()
@@ -136,7 +167,7 @@ mod tests {
         unsafe { env::set_var("TEST_SPECIAL", "value with spaces and $#@!") };
 
         let mut buffer = vec![];
-        print_env("{name} = \"{value}\"", &mut buffer);
+        print_env(&get_variables(), &default_template(), &mut buffer);
         assert!(
             String::from_utf8(buffer)
                 .unwrap()
@@ -205,6 +205,365 @@ fn load_custom_file_doesnt_exists() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+/// Test for load command if file references variable defined later in it
+fn load_reference_to_later_key() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    let file = assert_fs::NamedTempFile::new(".env.test")?;
+    file.write_str("LATER_REF_FIRST=${LATER_REF_SECOND}\nLATER_REF_SECOND=file")?;
+    cmd.arg("load")
+        .arg("--file")
+        .arg(file.path())
+        .arg("--dry-run")
+        .env("LATER_REF_SECOND", "env")
+        .env_remove("LATER_REF_FIRST")
+        .assert()
+        .success()
+        .stdout("+ LATER_REF_FIRST = \"env\"\n~ LATER_REF_SECOND = \"env\" → \"file\"\n");
+    file.close().unwrap();
+    Ok(())
+}
+
+#[test]
+/// Test for load command in cascade mode with explanation of sources
+fn load_cascade_explain() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = assert_fs::TempDir::new()?;
+    dir.child(".env")
+        .write_str("CASCADE_BASE=base\nCASCADE_MODE=base")?;
+    dir.child(".env.test").write_str("CASCADE_MODE=test")?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.current_dir(dir.path())
+        .arg("load")
+        .arg("--cascade")
+        .arg("test")
+        .arg("--explain")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CASCADE_BASE: .env\n"))
+        .stdout(predicate::str::contains(
+            "CASCADE_MODE: .env.test (overrides .env)\n",
+        ));
+    dir.close()?;
+    Ok(())
+}
+
+#[test]
+/// Test for load command with JSON file
+fn load_json_file() -> Result<(), Box<dyn std::error::Error>> {
+    let file = assert_fs::NamedTempFile::new("env.json")?;
+    file.write_str(r#"{"LOAD_JSON_VAR": "from json"}"#)?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("load").arg("--file").arg(file.path());
+    #[cfg(target_os = "windows")]
+    cmd.arg("--").arg("echo %LOAD_JSON_VAR%");
+    #[cfg(not(target_os = "windows"))]
+    cmd.arg("--").arg("echo $LOAD_JSON_VAR");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("from json"));
+    file.close().unwrap();
+    Ok(())
+}
+
+#[test]
+/// Test for load command with invalid YAML file
+fn load_invalid_yaml_file() -> Result<(), Box<dyn std::error::Error>> {
+    let file = assert_fs::NamedTempFile::new("env.txt")?;
+    file.write_str("A: one\nB: [two\n")?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("load")
+        .arg("--file")
+        .arg(file.path())
+        .arg("--format")
+        .arg("yaml")
+        .arg("--")
+        .arg("echo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 3, column 1"));
+    file.close().unwrap();
+    Ok(())
+}
+
+#[test]
+/// Test for load command reading variables from standard input
+fn load_from_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = assert_cmd::Command::cargo_bin("envfetch")?;
+    cmd.arg("load")
+        .arg("--file")
+        .arg("-")
+        .write_stdin("LOAD_STDIN_VAR=from stdin\n");
+    #[cfg(target_os = "windows")]
+    cmd.arg("--").arg("echo %LOAD_STDIN_VAR%");
+    #[cfg(not(target_os = "windows"))]
+    cmd.arg("--").arg("echo $LOAD_STDIN_VAR");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("from stdin"));
+    Ok(())
+}
+
+#[test]
+/// Test for load command with inline content
+fn load_env_string() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("load")
+        .arg("--env-string")
+        .arg("LOAD_INLINE_A=1\\nLOAD_INLINE_B=${LOAD_INLINE_A}2");
+    #[cfg(target_os = "windows")]
+    cmd.arg("--").arg("echo %LOAD_INLINE_B%");
+    #[cfg(not(target_os = "windows"))]
+    cmd.arg("--").arg("echo $LOAD_INLINE_B");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("12"));
+    Ok(())
+}
+
+#[test]
+/// Test for load command with invalid inline content
+fn load_invalid_env_string() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("load")
+        .arg("--env-string")
+        .arg("A=1\\nINVALID NAME=2")
+        .arg("--")
+        .arg("echo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 2, column 9"));
+    Ok(())
+}
+
+#[test]
+/// Test for set command in dry run, which only prints changes
+fn set_dry_run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("set")
+        .arg("--global")
+        .arg("--dry-run")
+        .arg("SET_DRY_RUN_VAR=value")
+        .env_remove("SET_DRY_RUN_VAR")
+        .assert()
+        .success()
+        .stdout("+ SET_DRY_RUN_VAR = \"value\"\n");
+    Ok(())
+}
+
+#[test]
+/// Test for set command in dry run, which masks secrets unless --reveal is set
+fn set_dry_run_secret() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("set")
+        .arg("--dry-run")
+        .arg("SET_DRY_RUN_PASSWORD=hunter2")
+        .env_remove("SET_DRY_RUN_PASSWORD")
+        .assert()
+        .success()
+        .stdout("+ SET_DRY_RUN_PASSWORD = \"********\"\n");
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("set")
+        .arg("--dry-run")
+        .arg("--reveal")
+        .arg("SET_DRY_RUN_PASSWORD=hunter2")
+        .env_remove("SET_DRY_RUN_PASSWORD")
+        .assert()
+        .success()
+        .stdout("+ SET_DRY_RUN_PASSWORD = \"hunter2\"\n");
+    Ok(())
+}
+
+#[test]
+/// Test for export command, which prints variables in given format
+fn export_to_stdout() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("export")
+        .arg("-")
+        .arg("--output")
+        .arg("powershell")
+        .arg("--")
+        .arg("EXPORT_STDOUT_VAR")
+        .env("EXPORT_STDOUT_VAR", "it's")
+        .assert()
+        .success()
+        .stdout("$env:EXPORT_STDOUT_VAR = 'it''s'\n");
+    Ok(())
+}
+
+#[test]
+/// Test for export command, which writes file with exact name
+fn export_to_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = assert_fs::TempDir::new()?;
+    let file = dir.child("out.json");
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("export")
+        .arg(file.path())
+        .arg("--")
+        .arg("EXPORT_FILE_VAR")
+        .env("EXPORT_FILE_VAR", "value")
+        .assert()
+        .success();
+    file.assert("{\n  \"EXPORT_FILE_VAR\": \"value\"\n}\n");
+    dir.child("out.json.env").assert(predicate::path::missing());
+    Ok(())
+}
+
+#[test]
+/// Test for export command with patterns, which reports matched variables
+fn export_matching_variables() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("export")
+        .arg("-")
+        .arg("--match")
+        .arg("EXPORT_MATCH_*")
+        .arg("--exclude")
+        .arg("*_TOKEN")
+        .env("EXPORT_MATCH_B", "b")
+        .env("EXPORT_MATCH_A", "a")
+        .env("EXPORT_MATCH_TOKEN", "secret")
+        .env_remove("ENVFETCH_LOG")
+        .assert()
+        .success()
+        .stdout("EXPORT_MATCH_A=a\nEXPORT_MATCH_B=b\n")
+        .stderr("EXPORT_MATCH_*: EXPORT_MATCH_A, EXPORT_MATCH_B\n");
+
+    let file = assert_fs::NamedTempFile::new("matched.env")?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("export")
+        .arg(file.path())
+        .arg("--match")
+        .arg("EXPORT_MATCH_*")
+        .env("EXPORT_MATCH_A", "a")
+        .env_remove("ENVFETCH_LOG")
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("EXPORT_MATCH_*: EXPORT_MATCH_A\n");
+    assert_eq!(std::fs::read_to_string(file.path())?, "EXPORT_MATCH_A=a\n");
+    file.close()?;
+    Ok(())
+}
+
+#[test]
+/// Test for export command in merge mode, which keeps other lines of file
+fn export_merge_into_file() -> Result<(), Box<dyn std::error::Error>> {
+    let file = assert_fs::NamedTempFile::new("merge.env")?;
+    file.write_str("# Settings\nEXPORT_MERGE_VAR=old # comment\nOTHER=1\n")?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("export")
+        .arg(file.path())
+        .arg("--merge")
+        .arg("--")
+        .arg("EXPORT_MERGE_VAR")
+        .arg("EXPORT_MERGE_NEW")
+        .env("EXPORT_MERGE_VAR", "new")
+        .env("EXPORT_MERGE_NEW", "a b")
+        .assert()
+        .success();
+    file.assert("# Settings\nEXPORT_MERGE_VAR=new # comment\nOTHER=1\nEXPORT_MERGE_NEW='a b'\n");
+    Ok(())
+}
+
+#[test]
+/// Test for exported dotenv file, which is loaded back with same values
+fn export_load_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let file = assert_fs::NamedTempFile::new("round-trip.env")?;
+    let value = " it's \"quoted\" # $HOME\nnext line\\ ";
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("export")
+        .arg(file.path())
+        .arg("--")
+        .arg("EXPORT_ROUND_TRIP_VAR")
+        .env("EXPORT_ROUND_TRIP_VAR", value)
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("load")
+        .arg("--dry-run")
+        .arg("--file")
+        .arg(file.path())
+        .env_remove("EXPORT_ROUND_TRIP_VAR")
+        .assert()
+        .success()
+        .stdout(format!("+ EXPORT_ROUND_TRIP_VAR = {:?}\n", value));
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+/// Test for snapshot commands, which store snapshots in config directory
+fn snapshot_save_diff_restore() -> Result<(), Box<dyn std::error::Error>> {
+    let home = assert_fs::TempDir::new()?;
+    let envfetch = || -> Result<Command, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("envfetch")?;
+        cmd.env("HOME", home.path())
+            .env("XDG_CONFIG_HOME", home.path().join("config"));
+        Ok(cmd)
+    };
+
+    envfetch()?
+        .arg("snapshot")
+        .arg("save")
+        .arg("base")
+        .env("SNAPSHOT_CLI_VAR", "old")
+        .env_remove("SNAPSHOT_CLI_NEW")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Saved snapshot base"));
+
+    envfetch()?
+        .arg("snapshot")
+        .arg("diff")
+        .arg("base")
+        .arg("current")
+        .env("SNAPSHOT_CLI_VAR", "new")
+        .env("SNAPSHOT_CLI_NEW", "added")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "+ SNAPSHOT_CLI_NEW = \"added\"\n~ SNAPSHOT_CLI_VAR = \"old\" → \"new\"\n",
+        ));
+
+    envfetch()?
+        .arg("snapshot")
+        .arg("restore")
+        .arg("base")
+        .arg("--")
+        .arg("echo \"$SNAPSHOT_CLI_VAR-${SNAPSHOT_CLI_NEW:-unset}\"")
+        .env("SNAPSHOT_CLI_VAR", "new")
+        .env("SNAPSHOT_CLI_NEW", "added")
+        .assert()
+        .success()
+        .stdout("old-unset\n");
+    Ok(())
+}
+
+#[test]
+/// Test for lint command, which fails if file has errors
+fn lint_file_with_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let file = assert_fs::NamedTempFile::new(".env.lint")?;
+    file.write_str("A=1\nB=\"unterminated\n")?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("lint")
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            ":2:3: error: unterminated quoted value",
+        ));
+
+    file.write_str("A=1\nB=2\n")?;
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.arg("lint")
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout("No problems found\n");
+    file.close().unwrap();
+    Ok(())
+}
+
 #[test]
 fn test_add_local_variable() -> Result<(), Box<dyn std::error::Error>> {
     let envfetch = Command::cargo_bin("envfetch")?
@@ -278,7 +637,7 @@ fn test_add_invalid_variable_name() -> Result<(), Box<dyn std::error::Error>> {
         .assert()
         .failure()
         .stderr(predicates::str::contains(
-            "Variable name cannot contain spaces",
+            "Invalid character ' ' at position 8 in variable name 'INVALID NAME'",
         ));
     Ok(())
 }
@@ -325,3 +684,219 @@ fn print_with_custom_format() -> Result<(), Box<dyn std::error::Error>> {
         .stdout(predicate::str::contains("FORMAT_TEST=Hello"));
     Ok(())
 }
+
+#[test]
+/// Test for print command with structured output
+fn print_with_json_output() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("PRINT_JSON_TEST", "say \"hi\"")
+        .arg("print")
+        .arg("--output")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""PRINT_JSON_TEST": "say \"hi\"""#,
+        ));
+    Ok(())
+}
+
+#[test]
+/// Test for print command with filters
+fn print_with_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("PRINT_FILTER_AWS", "aws")
+        .env("PRINT_FILTER_OTHER", "other")
+        .args(["print", "--match", "PRINT_FILTER_A*", "--format", "{name}"])
+        .assert()
+        .success()
+        .stdout("PRINT_FILTER_AWS\n");
+    Ok(())
+}
+
+#[test]
+/// Test for get command with secret-looking variable
+fn get_secret_variable_masked() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("GET_MASK_TEST_TOKEN", "hunter2")
+        .args(["get", "GET_MASK_TEST_TOKEN"])
+        .assert()
+        .success()
+        .stdout("\"********\"\n");
+
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("GET_MASK_TEST_TOKEN", "hunter2")
+        .args(["get", "GET_MASK_TEST_TOKEN", "--reveal"])
+        .assert()
+        .success()
+        .stdout("\"hunter2\"\n");
+    Ok(())
+}
+
+#[test]
+/// Test for get command with multiple variables and raw output
+fn get_multiple_variables_raw() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("GET_RAW_A", "it's raw")
+        .env_remove("GET_RAW_MISSING")
+        .args(["get", "GET_RAW_A", "GET_RAW_MISSING", "--raw"])
+        .assert()
+        .code(2)
+        .stdout("it's raw\n\n");
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("GET_RAW_A", "it's raw")
+        .env_remove("GET_RAW_MISSING")
+        .args([
+            "get",
+            "GET_RAW_A",
+            "GET_RAW_MISSING",
+            "--raw",
+            "--default",
+            "-",
+        ])
+        .assert()
+        .success()
+        .stdout("it's raw\n-\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test for path append command with process
+fn path_append_with_process() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("PATH_APPEND_TEST", "/a:/b")
+        .args(["path", "append", "PATH_APPEND_TEST", "/a", "/c"])
+        .arg("--")
+        .arg("echo $PATH_APPEND_TEST")
+        .assert()
+        .success()
+        .stdout("/b:/a:/c\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test for set command with multiple variables
+fn set_multiple_variables() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.args(["set", "SET_MULTI_A=Hello", "SET_MULTI_B=World"])
+        .arg("--")
+        .arg("echo $SET_MULTI_A $SET_MULTI_B")
+        .assert()
+        .success()
+        .stdout("Hello World\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test for delete command with multiple variables
+fn delete_multiple_variables() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("DELETE_MULTI_A", "Hello")
+        .env("DELETE_MULTI_B", "World")
+        .args(["delete", "DELETE_MULTI_A", "DELETE_MULTI_B"])
+        .arg("--")
+        .arg("echo \"[$DELETE_MULTI_A$DELETE_MULTI_B]\"")
+        .assert()
+        .success()
+        .stdout("[]\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test for running process without shell in place of envfetch
+fn set_without_shell_exec() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.args(["set", "EXEC_VAR=it's $HOME", "--no-shell", "--exec"])
+        .args([
+            "--",
+            "sh",
+            "-c",
+            "printf '%s|%s' \"$EXEC_VAR\" \"$1\"",
+            "sh",
+            "a 'b' c",
+        ])
+        .assert()
+        .success()
+        .stdout("it's $HOME|a 'b' c");
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test that child process killed by signal is reported with 128 + signal exit code
+fn set_process_killed_by_signal() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.args(["set", "SIGNAL_VAR=1", "--", "kill -KILL $$"])
+        .assert()
+        .code(137);
+    Ok(())
+}
+
+/// Send signal to envfetch running trapping process and check that process handles it
+#[cfg(not(target_os = "windows"))]
+fn assert_signal_forwarded(signal: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("envfetch"))
+        .args(["set", "SIGNAL_VAR=1", "--"])
+        .arg(format!(
+            "trap 'echo caught; exit 3' {signal}; echo ready; while true; do sleep 0.1; done"
+        ))
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line)?;
+    assert_eq!(line, "ready\n");
+
+    std::process::Command::new("kill")
+        .args([&format!("-{signal}"), &child.id().to_string()])
+        .status()?;
+    let status = child.wait()?;
+    line.clear();
+    stdout.read_line(&mut line)?;
+    assert_eq!(line, "caught\n");
+    assert_eq!(status.code(), Some(3));
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test that SIGTERM received by envfetch is forwarded to child process
+fn set_forwards_sigterm_to_process() -> Result<(), Box<dyn std::error::Error>> {
+    assert_signal_forwarded("TERM")
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test that SIGINT sent to envfetch by other process is forwarded to child process
+fn set_forwards_sigint_to_process() -> Result<(), Box<dyn std::error::Error>> {
+    assert_signal_forwarded("INT")
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+/// Test for running process in cleared environment
+fn set_with_clear_env() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("envfetch")?;
+    cmd.env("CLEAR_ENV_KEPT", "kept")
+        .env("CLEAR_ENV_DROPPED", "dropped")
+        .args([
+            "set",
+            "CLEAR_ENV_SET=set",
+            "--clear-env",
+            "--keep",
+            "CLEAR_ENV_KEPT",
+        ])
+        .arg("--")
+        .arg("echo \"[$CLEAR_ENV_SET][$CLEAR_ENV_KEPT][$CLEAR_ENV_DROPPED]\"")
+        .assert()
+        .success()
+        .stdout("[set][kept][]\n");
+    Ok(())
+}